@@ -0,0 +1,173 @@
+//! The comment thread of a plan, optionally by one of its users and about one date
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+use crate::types::{CommentText, PublicId};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "comments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub public_id: PublicId,
+    pub plan_id: i32,
+    /// `None` for comments written without picking a user
+    pub user_id: Option<i32>,
+    /// The date the comment is a note on
+    pub date: Option<TimeDate>,
+    pub text: CommentText,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plans::Entity",
+        from = "Column::PlanId",
+        to = "super::plans::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Plans,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Plans.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub struct NewComment {
+    plan_id: i32,
+    user_id: Option<i32>,
+    date: Option<TimeDate>,
+    text: CommentText,
+}
+
+impl NewComment {
+    pub fn new(
+        plan_id: i32,
+        user_id: Option<i32>,
+        date: Option<TimeDate>,
+        text: CommentText,
+    ) -> Self {
+        NewComment {
+            plan_id,
+            user_id,
+            date,
+            text,
+        }
+    }
+}
+
+impl IntoActiveModel<ActiveModel> for NewComment {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            public_id: Set(PublicId::default()),
+            plan_id: Set(self.plan_id),
+            user_id: Set(self.user_id),
+            date: Set(self.date),
+            text: Set(self.text),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Maximum number of comments a single plan can have
+pub const MAX_COMMENTS_PER_PLAN: u64 = 200;
+
+// region:	  --- Helpers
+pub mod helpers {
+    use super::{Column, Entity, Model, NewComment, MAX_COMMENTS_PER_PLAN};
+    use crate::{
+        db::ModelManager,
+        error::{Error, Result},
+        plans::{self, helpers::plan_id_by_public_id},
+        types::{CommentText, PublicId},
+        users,
+    };
+
+    use sea_orm::{prelude::*, IntoActiveModel, QueryOrder};
+    use tracing::instrument;
+
+    /// The author has to be a user of the plan
+    #[instrument(level = "debug", skip(mm, text))]
+    pub async fn create_comment(
+        plan_public_id: PublicId,
+        author_public_id: Option<PublicId>,
+        date: Option<TimeDate>,
+        text: CommentText,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+        let user_id = match author_public_id {
+            Some(author_public_id) => {
+                let author =
+                    users::helpers::user_by_public_id(author_public_id.clone(), mm.clone()).await?;
+                if author.plan_id != plan_id {
+                    return Err(Error::EntityNotFound(author_public_id.to_string()));
+                }
+                Some(author.id)
+            }
+            None => None,
+        };
+
+        // -- Check the comment limit, holding the plan's row so concurrent comments wait
+        let txn = plans::helpers::lock_plan(plan_id, &mm).await?;
+        let comment_count = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            .count(&txn)
+            .await?;
+        if comment_count >= MAX_COMMENTS_PER_PLAN {
+            return Err(Error::LimitReached {
+                what: "comments per plan",
+                max: MAX_COMMENTS_PER_PLAN,
+            });
+        }
+
+        let comment = NewComment::new(plan_id, user_id, date, text)
+            .into_active_model()
+            .insert(&txn)
+            .await?;
+        txn.commit().await?;
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(comment)
+    }
+
+    /// The plan's comments with their authors, oldest first
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn comments_for_plan_public_id(
+        plan_public_id: PublicId,
+        mm: ModelManager,
+    ) -> Result<Vec<(Model, Option<users::Model>)>> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        let comments = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            .order_by_asc(Column::Ctime)
+            .find_also_related(users::Entity)
+            .all(mm.db())
+            .await?;
+
+        Ok(comments)
+    }
+}
+// endregion: --- Helpers
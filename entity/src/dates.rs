@@ -56,25 +56,186 @@ impl IntoActiveModel<ActiveModel> for NewDate {
     }
 }
 
+/// Maximum number of dates a single user can pick
+pub const MAX_DATES_PER_USER: u64 = 366;
+
 // region:	  --- Helpers
 pub mod helpers {
-    use super::{Column, Entity, NewDate};
-    use crate::{db::ModelManager, error::Result, types::PublicId, users};
+    use super::{Column, Entity, NewDate, MAX_DATES_PER_USER};
+    use crate::{
+        db::ModelManager,
+        error::{Error, Result},
+        plans,
+        types::PublicId,
+        users,
+    };
 
-    use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter};
+    use sea_orm::{
+        sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityTrait,
+        IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+    };
     use time::Date;
+    use tracing::instrument;
 
+    #[instrument(level = "debug", skip(mm))]
     pub async fn user_add_date(public_id: PublicId, date: Date, mm: ModelManager) -> Result<()> {
         let user_id = users::helpers::user_id_by_public_id(public_id, mm.clone()).await?;
+        let txn = lock_user(user_id, &mm).await?;
+
+        // -- Check the date limit
+        let date_count = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .count(&txn)
+            .await?;
+        if date_count >= MAX_DATES_PER_USER {
+            return Err(Error::LimitReached {
+                what: "dates per user",
+                max: MAX_DATES_PER_USER,
+            });
+        }
 
         let _ = NewDate::new(date, user_id)
             .into_active_model()
-            .insert(mm.db())
+            .insert(&txn)
             .await?;
+        txn.commit().await?;
+        plans::helpers::touch_plan_of_user(user_id, mm).await?;
 
         Ok(())
     }
 
+    /// Adds the dates in one insert, skipping the ones the user already picked.
+    /// Returns the dates that were new.
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn user_add_dates(
+        public_id: PublicId,
+        dates: Vec<Date>,
+        mm: ModelManager,
+    ) -> Result<Vec<Date>> {
+        let user_id = users::helpers::user_id_by_public_id(public_id, mm.clone()).await?;
+        let txn = lock_user(user_id, &mm).await?;
+
+        let existing: Vec<Date> = Entity::find()
+            .select_only()
+            .column(Column::Date)
+            .filter(Column::UserId.eq(user_id))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        let mut new_dates: Vec<Date> = dates
+            .into_iter()
+            .filter(|date| !existing.contains(date))
+            .collect();
+        new_dates.sort();
+        new_dates.dedup();
+        if new_dates.is_empty() {
+            return Ok(new_dates);
+        }
+
+        // -- Check the date limit
+        if existing.len() + new_dates.len() > MAX_DATES_PER_USER as usize {
+            return Err(Error::LimitReached {
+                what: "dates per user",
+                max: MAX_DATES_PER_USER,
+            });
+        }
+
+        // Dates picked in the meantime are skipped as well
+        Entity::insert_many(
+            new_dates
+                .iter()
+                .map(|date| NewDate::new(*date, user_id).into_active_model()),
+        )
+        .on_conflict(
+            OnConflict::columns([Column::Date, Column::UserId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec_without_returning(&txn)
+        .await?;
+        txn.commit().await?;
+        plans::helpers::touch_plan_of_user(user_id, mm).await?;
+
+        Ok(new_dates)
+    }
+
+    /// Starts a transaction that holds the user's row, so concurrent adds can't both pass the
+    /// date limit
+    async fn lock_user(user_id: i32, mm: &ModelManager) -> Result<DatabaseTransaction> {
+        let txn = mm.db().begin().await?;
+        users::Entity::find_by_id(user_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(Error::EntityNotFound(user_id.to_string()))?;
+
+        Ok(txn)
+    }
+
+    /// The dates the user picked, earliest first
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn user_dates(public_id: PublicId, mm: ModelManager) -> Result<Vec<Date>> {
+        let user_id = users::helpers::user_id_by_public_id(public_id, mm.clone()).await?;
+
+        let dates = Entity::find()
+            .select_only()
+            .column(Column::Date)
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Date)
+            .into_tuple()
+            .all(mm.db())
+            .await?;
+
+        Ok(dates)
+    }
+
+    /// Removes the dates in one delete. Returns the dates the user had picked.
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn user_delete_dates(
+        public_id: PublicId,
+        dates: Vec<Date>,
+        mm: ModelManager,
+    ) -> Result<Vec<Date>> {
+        let picked = user_dates(public_id.clone(), mm.clone()).await?;
+        let removed: Vec<Date> = picked
+            .into_iter()
+            .filter(|date| dates.contains(date))
+            .collect();
+        if removed.is_empty() {
+            return Ok(removed);
+        }
+
+        let user_id = users::helpers::user_id_by_public_id(public_id, mm.clone()).await?;
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Date.is_in(removed.clone()))
+            .exec(mm.db())
+            .await?;
+        plans::helpers::touch_plan_of_user(user_id, mm).await?;
+
+        Ok(removed)
+    }
+
+    /// Removes every date of the user. Returns the dates the user had picked.
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn user_clear_dates(public_id: PublicId, mm: ModelManager) -> Result<Vec<Date>> {
+        let removed = user_dates(public_id.clone(), mm.clone()).await?;
+        if removed.is_empty() {
+            return Ok(removed);
+        }
+
+        let user_id = users::helpers::user_id_by_public_id(public_id, mm.clone()).await?;
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .exec(mm.db())
+            .await?;
+        plans::helpers::touch_plan_of_user(user_id, mm).await?;
+
+        Ok(removed)
+    }
+
+    #[instrument(level = "debug", skip(mm))]
     pub async fn user_delete_date(
         user_public_id: PublicId,
         date: time::Date,
@@ -89,6 +250,7 @@ pub mod helpers {
             .filter(Column::Date.eq(date))
             .exec(mm.db())
             .await?;
+        plans::helpers::touch_plan_of_user(user_id, mm).await?;
 
         Ok(())
     }
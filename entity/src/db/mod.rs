@@ -1,6 +1,6 @@
 pub use self::error::{Error, Result};
 use migration::MigratorTrait;
-use sea_orm::{entity::*, ConnectOptions, Database, DatabaseConnection};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 use std::time::Duration;
 
 pub mod error;
@@ -74,21 +74,24 @@ mod tests {
     use super::*;
     use crate::{
         dates, plans,
-        types::{PlanName, UserName},
+        types::{PlanName, TimeZoneName, UserName},
         users,
     };
     use anyhow::Result;
-    use sea_orm::{EntityTrait, IntoActiveModel, Set};
+    use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, ModelTrait, Set};
 
     #[tokio::test]
     async fn test_create_plan_ok() -> Result<()> {
         let mm = _dev_utils::init_test().await;
         let db = mm.db();
 
-        let new_plan = plans::NewPlan::new(PlanName::new("test_create_plan_ok").unwrap())
-            .into_active_model()
-            .insert(db)
-            .await?;
+        let new_plan = plans::NewPlan::new(
+            PlanName::new("test_create_plan_ok").unwrap(),
+            TimeZoneName::default(),
+        )
+        .into_active_model()
+        .insert(db)
+        .await?;
 
         // -- Check
         assert_eq!(new_plan.name.to_string(), "test_create_plan_ok".to_string());
@@ -103,10 +106,13 @@ mod tests {
         let mm = _dev_utils::init_test().await;
         let db = mm.db();
 
-        let new_plan = plans::NewPlan::new(PlanName::new("test_create_user_ok").unwrap())
-            .into_active_model()
-            .insert(db)
-            .await?;
+        let new_plan = plans::NewPlan::new(
+            PlanName::new("test_create_user_ok").unwrap(),
+            TimeZoneName::default(),
+        )
+        .into_active_model()
+        .insert(db)
+        .await?;
 
         let new_user =
             users::NewUser::new(UserName::new("test_create_user_ok").unwrap(), new_plan.id)
@@ -127,10 +133,13 @@ mod tests {
         let mm = _dev_utils::init_test().await;
         let db = mm.db();
 
-        let new_plan = plans::NewPlan::new(PlanName::new("test_create_date_ok").unwrap())
-            .into_active_model()
-            .insert(db)
-            .await?;
+        let new_plan = plans::NewPlan::new(
+            PlanName::new("test_create_date_ok").unwrap(),
+            TimeZoneName::default(),
+        )
+        .into_active_model()
+        .insert(db)
+        .await?;
 
         let new_user =
             users::NewUser::new(UserName::new("test_create_date_ok").unwrap(), new_plan.id)
@@ -152,5 +161,42 @@ mod tests {
         new_plan.delete(db).await?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_create_user_limit_err() -> Result<()> {
+        let mm = _dev_utils::init_test().await;
+        let db = mm.db();
+
+        let new_plan = plans::NewPlan::new(
+            PlanName::new("test_create_user_limit_err").unwrap(),
+            TimeZoneName::default(),
+        )
+        .into_active_model()
+        .insert(db)
+        .await?;
+
+        for i in 0..users::MAX_USERS_PER_PLAN {
+            users::helpers::create_user_for_plan(
+                new_plan.public_id.clone(),
+                UserName::new(&format!("user{i}")).unwrap(),
+                mm.clone(),
+            )
+            .await?;
+        }
+
+        let res = users::helpers::create_user_for_plan(
+            new_plan.public_id.clone(),
+            UserName::new("one_too_many").unwrap(),
+            mm.clone(),
+        )
+        .await;
+
+        // -- Check
+        assert!(matches!(res, Err(crate::error::Error::LimitReached { .. })));
+
+        // -- Cleanup
+        new_plan.delete(db).await?;
+        Ok(())
+    }
 }
 // endregion: --- Tests
@@ -6,6 +6,8 @@ pub enum Error {
     // -- Operation Errors
     #[error("Entity was not found based on {0}")]
     EntityNotFound(String),
+    #[error("Limit reached: at most {max} {what}")]
+    LimitReached { what: &'static str, max: u64 },
     // -- Database
     #[error("Database error: {0}")]
     DbErr(#[from] sea_orm::DbErr),
@@ -11,9 +11,16 @@ pub mod error;
 pub mod prelude;
 pub mod types;
 
+pub mod comments;
 pub mod dates;
 pub mod plans;
+pub mod poll_options;
+pub mod poll_questions;
+pub mod poll_votes;
+pub mod quorum_dates;
 pub mod users;
+pub mod webhook_deliveries;
+pub mod webhooks;
 
 // Define the cache using `Lazy` and `DashMap`
 static ID_MAP_CACHE: Lazy<Arc<DashMap<PublicId, i32>>> = Lazy::new(|| Arc::new(DashMap::new()));
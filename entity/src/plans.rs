@@ -4,7 +4,7 @@ use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
 
 use crate::{
     db::ModelManager,
-    types::{Description, PlanName, PublicId},
+    types::{Description, PlanName, PublicId, Secret, TimeZoneName, WeekStart},
     users,
 };
 
@@ -17,6 +17,20 @@ pub struct Model {
     pub name: PlanName,
     pub description: Option<Description>,
     pub ctime: TimeDateTimeWithTimeZone,
+    pub mtime: TimeDateTimeWithTimeZone,
+    /// `None` follows the viewer's locale
+    pub week_start: Option<WeekStart>,
+    pub show_week_numbers: bool,
+    /// Zone of the plan, used for "today" when the viewer's zone is unknown
+    pub time_zone: TimeZoneName,
+    /// Held by the browser that created the plan, `None` for plans from before owners
+    pub owner_secret: Option<Secret>,
+    /// Attendance a date needs to be workable, `None` for no minimum
+    pub min_attendance: Option<i32>,
+    /// The date the owner settled on, `None` while the plan is open
+    pub final_date: Option<TimeDate>,
+    /// First day of the month the plan is about, `None` for the first upcoming candidate
+    pub window_start: Option<TimeDate>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -35,11 +49,12 @@ impl ActiveModelBehavior for ActiveModel {}
 
 pub struct NewPlan {
     name: PlanName,
+    time_zone: TimeZoneName,
 }
 
 impl NewPlan {
-    pub fn new(name: PlanName) -> Self {
-        NewPlan { name }
+    pub fn new(name: PlanName, time_zone: TimeZoneName) -> Self {
+        NewPlan { name, time_zone }
     }
 }
 
@@ -50,6 +65,14 @@ impl IntoActiveModel<ActiveModel> for NewPlan {
             description: Set(None),
             public_id: Set(PublicId::default()),
             ctime: Set(time::OffsetDateTime::now_utc()),
+            mtime: Set(time::OffsetDateTime::now_utc()),
+            week_start: Set(None),
+            show_week_numbers: Set(false),
+            time_zone: Set(self.time_zone),
+            owner_secret: Set(Some(Secret::default())),
+            min_attendance: Set(None),
+            final_date: Set(None),
+            window_start: Set(None),
             ..Default::default()
         }
     }
@@ -63,22 +86,88 @@ impl Model {
 
 // region:	  --- Helper functions
 pub mod helpers {
-    use super::{Column, Entity, Model, NewPlan};
+    use super::{ActiveModel, Column, Entity, Model, NewPlan};
     use crate::{
         db::ModelManager,
         error::{Error, Result},
-        types::{PlanName, PublicId},
-        ID_MAP_CACHE,
+        types::{PlanName, PublicId, Secret, TimeZoneName, WeekStart},
+        users, ID_MAP_CACHE,
     };
-    use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter};
+    use sea_orm::{
+        sea_query::{Expr, Query},
+        ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityTrait, IntoActiveModel,
+        QueryFilter, QuerySelect, Set, TransactionTrait,
+    };
+    use tracing::instrument;
 
-    pub async fn create_plan(plan_name: PlanName, mm: ModelManager) -> Result<Model> {
-        let new_plan = NewPlan::new(plan_name);
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn create_plan(
+        plan_name: PlanName,
+        time_zone: TimeZoneName,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let new_plan = NewPlan::new(plan_name, time_zone);
         let new_plan_entity = new_plan.into_active_model().insert(mm.db()).await?;
 
         Ok(new_plan_entity)
     }
 
+    /// A new plan with the settings and participants of the original, but none of the dates.
+    /// The copy gets its own owner secret and email tokens.
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn clone_plan(
+        public_id: PublicId,
+        window_start: Option<time::Date>,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let plan = plan_by_public_id(public_id, mm.clone()).await?;
+        let participants = plan.get_users(mm.clone()).await?;
+        let now = time::OffsetDateTime::now_utc();
+
+        // A plan without its participants would be left behind on failure
+        let txn = mm.db().begin().await?;
+        let new_plan = ActiveModel {
+            public_id: Set(PublicId::default()),
+            name: Set(plan.name),
+            description: Set(plan.description),
+            ctime: Set(now),
+            mtime: Set(now),
+            week_start: Set(plan.week_start),
+            show_week_numbers: Set(plan.show_week_numbers),
+            time_zone: Set(plan.time_zone),
+            owner_secret: Set(Some(Secret::default())),
+            min_attendance: Set(plan.min_attendance),
+            final_date: Set(None),
+            window_start: Set(window_start),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        for participant in participants {
+            users::ActiveModel {
+                public_id: Set(PublicId::default()),
+                name: Set(participant.name),
+                plan_id: Set(new_plan.id),
+                ctime: Set(now),
+                required: Set(participant.required),
+                email_token: Set(participant.email.as_ref().map(|_| Secret::default())),
+                email: Set(participant.email),
+                email_confirmed: Set(participant.email_confirmed),
+                email_notify: Set(participant.email_notify),
+                email_locale: Set(participant.email_locale),
+                digest_sent_at: Set(Some(now)),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(new_plan)
+    }
+
+    #[instrument(level = "debug", skip(mm))]
     pub async fn plan_id_by_public_id(public_id: PublicId, mm: ModelManager) -> Result<i32> {
         // First, check if the user is already in the cache
         if let Some(cached_plan_id) = ID_MAP_CACHE.get(&public_id) {
@@ -92,6 +181,7 @@ pub mod helpers {
         Ok(id)
     }
 
+    #[instrument(level = "debug", skip(mm))]
     pub async fn plan_by_public_id(id: PublicId, mm: ModelManager) -> Result<Model> {
         let plan = Entity::find()
             .filter(Column::PublicId.eq(id.clone()))
@@ -101,5 +191,98 @@ pub mod helpers {
 
         Ok(plan)
     }
+
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn plan_by_id(id: i32, mm: ModelManager) -> Result<Model> {
+        let plan = Entity::find_by_id(id)
+            .one(mm.db())
+            .await?
+            .ok_or(Error::EntityNotFound(id.to_string()))?;
+
+        Ok(plan)
+    }
+
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn update_calendar_settings(
+        public_id: PublicId,
+        week_start: Option<WeekStart>,
+        show_week_numbers: bool,
+        time_zone: TimeZoneName,
+        min_attendance: Option<i32>,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let mut plan = plan_by_public_id(public_id, mm.clone())
+            .await?
+            .into_active_model();
+
+        plan.week_start = Set(week_start);
+        plan.show_week_numbers = Set(show_week_numbers);
+        plan.time_zone = Set(time_zone);
+        plan.min_attendance = Set(min_attendance);
+        plan.mtime = Set(time::OffsetDateTime::now_utc());
+
+        Ok(plan.update(mm.db()).await?)
+    }
+
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn set_final_date(
+        public_id: PublicId,
+        final_date: Option<time::Date>,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let mut plan = plan_by_public_id(public_id, mm.clone())
+            .await?
+            .into_active_model();
+
+        plan.final_date = Set(final_date);
+        plan.mtime = Set(time::OffsetDateTime::now_utc());
+
+        Ok(plan.update(mm.db()).await?)
+    }
+
+    /// Starts a transaction that holds the plan's row, so concurrent inserts can't both pass a
+    /// per plan limit
+    pub async fn lock_plan(plan_id: i32, mm: &ModelManager) -> Result<DatabaseTransaction> {
+        let txn = mm.db().begin().await?;
+        Entity::find_by_id(plan_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(Error::EntityNotFound(plan_id.to_string()))?;
+
+        Ok(txn)
+    }
+
+    /// Marks the plan as changed now
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn touch_plan(plan_id: i32, mm: ModelManager) -> Result<()> {
+        Entity::update_many()
+            .col_expr(Column::Mtime, Expr::current_timestamp().into())
+            .filter(Column::Id.eq(plan_id))
+            .exec(mm.db())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks the plan the user belongs to as changed now
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn touch_plan_of_user(user_id: i32, mm: ModelManager) -> Result<()> {
+        Entity::update_many()
+            .col_expr(Column::Mtime, Expr::current_timestamp().into())
+            .filter(
+                Column::Id.in_subquery(
+                    Query::select()
+                        .column(users::Column::PlanId)
+                        .from(users::Entity)
+                        .and_where(users::Column::Id.eq(user_id))
+                        .to_owned(),
+                ),
+            )
+            .exec(mm.db())
+            .await?;
+
+        Ok(())
+    }
 }
 // endregion: --- Helper functions
@@ -0,0 +1,67 @@
+//! The answers to choose from in a poll question
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+use crate::types::{PollOptionText, PublicId};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "poll_options")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub public_id: PublicId,
+    pub question_id: i32,
+    pub text: PollOptionText,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::poll_votes::Entity")]
+    PollVotes,
+    #[sea_orm(
+        belongs_to = "super::poll_questions::Entity",
+        from = "Column::QuestionId",
+        to = "super::poll_questions::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    PollQuestions,
+}
+
+impl Related<super::poll_votes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PollVotes.def()
+    }
+}
+
+impl Related<super::poll_questions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PollQuestions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub struct NewPollOption {
+    question_id: i32,
+    text: PollOptionText,
+}
+
+impl NewPollOption {
+    pub fn new(question_id: i32, text: PollOptionText) -> Self {
+        NewPollOption { question_id, text }
+    }
+}
+
+impl IntoActiveModel<ActiveModel> for NewPollOption {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            public_id: Set(PublicId::default()),
+            question_id: Set(self.question_id),
+            text: Set(self.text),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Questions of a plan besides the date, answered by voting on their options
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+use crate::types::{PollQuestionText, PublicId};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "poll_questions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub public_id: PublicId,
+    pub plan_id: i32,
+    pub text: PollQuestionText,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::poll_options::Entity")]
+    PollOptions,
+    #[sea_orm(
+        belongs_to = "super::plans::Entity",
+        from = "Column::PlanId",
+        to = "super::plans::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Plans,
+}
+
+impl Related<super::poll_options::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PollOptions.def()
+    }
+}
+
+impl Related<super::plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Plans.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub struct NewPollQuestion {
+    plan_id: i32,
+    text: PollQuestionText,
+}
+
+impl NewPollQuestion {
+    pub fn new(plan_id: i32, text: PollQuestionText) -> Self {
+        NewPollQuestion { plan_id, text }
+    }
+}
+
+impl IntoActiveModel<ActiveModel> for NewPollQuestion {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            public_id: Set(PublicId::default()),
+            plan_id: Set(self.plan_id),
+            text: Set(self.text),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
+
+/// An option with every vote on it
+pub type OptionWithVotes = (super::poll_options::Model, Vec<super::poll_votes::Model>);
+
+/// A question with its options, in the order they were given
+pub type PollWithOptions = (Model, Vec<OptionWithVotes>);
+
+/// Maximum number of questions a single plan can have
+pub const MAX_POLLS_PER_PLAN: u64 = 10;
+
+/// Maximum number of options a single question can have
+pub const MAX_OPTIONS_PER_POLL: usize = 20;
+
+// region:	  --- Helpers
+pub mod helpers {
+    use std::collections::HashMap;
+
+    use super::{
+        Column, Entity, NewPollQuestion, PollWithOptions, MAX_OPTIONS_PER_POLL, MAX_POLLS_PER_PLAN,
+    };
+    use crate::{
+        db::ModelManager,
+        error::{Error, Result},
+        plans::{self, helpers::plan_id_by_public_id},
+        poll_options::{self, NewPollOption},
+        poll_votes,
+        types::{PollOptionText, PollQuestionText, PublicId},
+    };
+
+    use sea_orm::{prelude::*, IntoActiveModel, QueryOrder};
+    use tracing::instrument;
+
+    /// Creates the question together with its options
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn create_poll(
+        plan_public_id: PublicId,
+        text: PollQuestionText,
+        options: Vec<PollOptionText>,
+        mm: ModelManager,
+    ) -> Result<()> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        if options.len() > MAX_OPTIONS_PER_POLL {
+            return Err(Error::LimitReached {
+                what: "options per poll",
+                max: MAX_OPTIONS_PER_POLL as u64,
+            });
+        }
+
+        // -- Check the poll limit, holding the plan's row so concurrent polls wait
+        let txn = plans::helpers::lock_plan(plan_id, &mm).await?;
+        let poll_count = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            .count(&txn)
+            .await?;
+        if poll_count >= MAX_POLLS_PER_PLAN {
+            return Err(Error::LimitReached {
+                what: "polls per plan",
+                max: MAX_POLLS_PER_PLAN,
+            });
+        }
+
+        // In the same transaction, a question without its options would be left behind on failure
+        let question = NewPollQuestion::new(plan_id, text)
+            .into_active_model()
+            .insert(&txn)
+            .await?;
+        for option in options {
+            NewPollOption::new(question.id, option)
+                .into_active_model()
+                .insert(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(())
+    }
+
+    /// Only deletes the question when it belongs to the plan, its options and votes go with it
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn delete_poll(
+        plan_public_id: PublicId,
+        poll_public_id: PublicId,
+        mm: ModelManager,
+    ) -> Result<()> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        let deleted = Entity::delete_many()
+            .filter(Column::PlanId.eq(plan_id))
+            .filter(Column::PublicId.eq(poll_public_id.clone()))
+            .exec(mm.db())
+            .await?;
+        if deleted.rows_affected == 0 {
+            return Err(Error::EntityNotFound(poll_public_id.to_string()));
+        }
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(())
+    }
+
+    /// The plan's questions with their options and votes, oldest first
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn polls_for_plan_public_id(
+        plan_public_id: PublicId,
+        mm: ModelManager,
+    ) -> Result<Vec<PollWithOptions>> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        let questions = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            // Rows of one question have to be next to each other to be grouped
+            .order_by_asc(Column::Id)
+            .find_with_related(poll_options::Entity)
+            .all(mm.db())
+            .await?;
+        let option_ids: Vec<i32> = questions
+            .iter()
+            .flat_map(|(_, options)| options.iter().map(|option| option.id))
+            .collect();
+
+        let mut votes_by_option: HashMap<i32, Vec<poll_votes::Model>> = HashMap::new();
+        if !option_ids.is_empty() {
+            for vote in poll_votes::Entity::find()
+                .filter(poll_votes::Column::OptionId.is_in(option_ids))
+                .all(mm.db())
+                .await?
+            {
+                votes_by_option
+                    .entry(vote.option_id)
+                    .or_default()
+                    .push(vote);
+            }
+        }
+
+        let polls = questions
+            .into_iter()
+            .map(|(question, mut options)| {
+                options.sort_by_key(|option| option.id);
+                let options = options
+                    .into_iter()
+                    .map(|option| {
+                        let votes = votes_by_option.remove(&option.id).unwrap_or_default();
+                        (option, votes)
+                    })
+                    .collect();
+                (question, options)
+            })
+            .collect();
+
+        Ok(polls)
+    }
+}
+// endregion: --- Helpers
@@ -0,0 +1,132 @@
+//! Yes, maybe or no of a user on a poll option
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+use crate::types::Vote;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "poll_votes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub option_id: i32,
+    pub user_id: i32,
+    pub vote: Vote,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::poll_options::Entity",
+        from = "Column::OptionId",
+        to = "super::poll_options::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    PollOptions,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::poll_options::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PollOptions.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub struct NewPollVote {
+    option_id: i32,
+    user_id: i32,
+    vote: Vote,
+}
+
+impl NewPollVote {
+    pub fn new(option_id: i32, user_id: i32, vote: Vote) -> Self {
+        NewPollVote {
+            option_id,
+            user_id,
+            vote,
+        }
+    }
+}
+
+impl IntoActiveModel<ActiveModel> for NewPollVote {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            option_id: Set(self.option_id),
+            user_id: Set(self.user_id),
+            vote: Set(self.vote),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
+
+// region:	  --- Helpers
+pub mod helpers {
+    use super::{Column, Entity, NewPollVote};
+    use crate::{
+        db::ModelManager,
+        error::{Error, Result},
+        plans::{self, helpers::plan_id_by_public_id},
+        poll_options, poll_questions,
+        types::{PublicId, Vote},
+        users,
+    };
+
+    use sea_orm::{prelude::*, sea_query::OnConflict, IntoActiveModel};
+    use tracing::instrument;
+
+    /// Replaces the user's earlier vote on the option, both have to belong to the plan
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn set_vote(
+        plan_public_id: PublicId,
+        user_public_id: PublicId,
+        option_public_id: PublicId,
+        vote: Vote,
+        mm: ModelManager,
+    ) -> Result<()> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+        let user = users::helpers::user_by_public_id(user_public_id.clone(), mm.clone()).await?;
+        if user.plan_id != plan_id {
+            return Err(Error::EntityNotFound(user_public_id.to_string()));
+        }
+        let option = poll_options::Entity::find()
+            .filter(poll_options::Column::PublicId.eq(option_public_id.clone()))
+            .find_also_related(poll_questions::Entity)
+            .one(mm.db())
+            .await?;
+        let option_id = match option {
+            Some((option, Some(question))) if question.plan_id == plan_id => option.id,
+            _ => return Err(Error::EntityNotFound(option_public_id.to_string())),
+        };
+
+        Entity::insert(NewPollVote::new(option_id, user.id, vote).into_active_model())
+            .on_conflict(
+                OnConflict::columns([Column::OptionId, Column::UserId])
+                    .update_columns([Column::Vote, Column::Ctime])
+                    .to_owned(),
+            )
+            .exec(mm.db())
+            .await?;
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(())
+    }
+}
+// endregion: --- Helpers
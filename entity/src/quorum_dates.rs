@@ -0,0 +1,83 @@
+//! Dates on which a plan reached its minimum attendance at least once
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "quorum_dates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub plan_id: i32,
+    pub date: TimeDate,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plans::Entity",
+        from = "Column::PlanId",
+        to = "super::plans::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Plans,
+}
+
+impl Related<super::plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Plans.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub struct NewQuorumDate {
+    plan_id: i32,
+    date: time::Date,
+}
+
+impl NewQuorumDate {
+    pub fn new(plan_id: i32, date: time::Date) -> Self {
+        NewQuorumDate { plan_id, date }
+    }
+}
+
+impl IntoActiveModel<ActiveModel> for NewQuorumDate {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            plan_id: Set(self.plan_id),
+            date: Set(self.date),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
+
+// region:	  --- Helpers
+pub mod helpers {
+    use super::{Column, Entity, NewQuorumDate};
+    use crate::{db::ModelManager, error::Result};
+
+    use sea_orm::{sea_query::OnConflict, EntityTrait, IntoActiveModel};
+    use time::Date;
+    use tracing::instrument;
+
+    /// Remembers that the plan reached its quorum on `date`.
+    ///
+    /// Returns `true` only the first time, so the caller can announce it once.
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn record_quorum_reached(plan_id: i32, date: Date, mm: ModelManager) -> Result<bool> {
+        let inserted = Entity::insert(NewQuorumDate::new(plan_id, date).into_active_model())
+            .on_conflict(
+                OnConflict::columns([Column::PlanId, Column::Date])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(mm.db())
+            .await?;
+
+        Ok(inserted > 0)
+    }
+}
+// endregion: --- Helpers
@@ -2,7 +2,7 @@
 
 use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
 
-use crate::types::{PublicId, UserName};
+use crate::types::{EmailAddress, PublicId, Secret, UserName};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "users")]
@@ -13,6 +13,28 @@ pub struct Model {
     pub name: UserName,
     pub plan_id: i32,
     pub ctime: TimeDateTimeWithTimeZone,
+    /// Dates without every required user are not viable
+    pub required: bool,
+    pub email: Option<EmailAddress>,
+    /// Proves the links in emails were sent to this user
+    pub email_token: Option<Secret>,
+    /// Only confirmed addresses receive notifications
+    pub email_confirmed: bool,
+    /// `false` once the user unsubscribed
+    pub email_notify: bool,
+    /// Responses after this go in the next digest
+    pub digest_sent_at: Option<TimeDateTimeWithTimeZone>,
+    /// Language tag of the page the address was given on, the emails are in that language
+    pub email_locale: Option<String>,
+}
+
+impl Model {
+    /// The user wants notifications and confirmed where to send them
+    pub fn notification_email(&self) -> Option<&EmailAddress> {
+        self.email
+            .as_ref()
+            .filter(|_| self.email_confirmed && self.email_notify)
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -61,41 +83,69 @@ impl IntoActiveModel<ActiveModel> for NewUser {
             plan_id: Set(self.plan_id),
             public_id: Set(PublicId::default()),
             ctime: Set(time::OffsetDateTime::now_utc()),
+            required: Set(false),
+            email: Set(None),
+            email_token: Set(None),
+            email_confirmed: Set(false),
+            email_notify: Set(true),
+            digest_sent_at: Set(None),
+            email_locale: Set(None),
             ..Default::default()
         }
     }
 }
 
+/// Maximum number of participants a single plan can have
+pub const MAX_USERS_PER_PLAN: u64 = 50;
+
 // region:	  --- Helper functions
 pub mod helpers {
 
-    use super::{Column, Entity, Model};
+    use super::{Column, Entity, Model, MAX_USERS_PER_PLAN};
     use crate::{
         dates,
         db::ModelManager,
         error::{Error, Result},
         plans::{self, helpers::plan_id_by_public_id},
-        types::{PublicId, UserName},
+        types::{EmailAddress, PublicId, Secret, UserName},
         users, ID_MAP_CACHE,
     };
-    use sea_orm::{prelude::*, IntoActiveModel};
+    use sea_orm::{prelude::*, IntoActiveModel, Set};
+    use tracing::instrument;
 
+    #[instrument(level = "debug", skip(mm))]
     pub async fn create_user_for_plan(
         plan_public_id: PublicId,
         username: UserName,
         mm: ModelManager,
     ) -> Result<Model> {
         // -- Get the plan id
-        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+        let plan_id = plan_id_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+
+        // -- Check the participant limit, holding the plan's row so concurrent joins wait
+        let txn = plans::helpers::lock_plan(plan_id, &mm).await?;
+        let user_count = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            .count(&txn)
+            .await?;
+        if user_count >= MAX_USERS_PER_PLAN {
+            return Err(Error::LimitReached {
+                what: "participants per plan",
+                max: MAX_USERS_PER_PLAN,
+            });
+        }
 
         // TODO: Give clear error to user when username already exists
         // -- Insert new user
         let new_user = users::NewUser::new(username, plan_id);
-        let new_user_model = new_user.into_active_model().insert(mm.db()).await?;
+        let new_user_model = new_user.into_active_model().insert(&txn).await?;
+        txn.commit().await?;
+        plans::helpers::touch_plan(plan_id, mm).await?;
 
         Ok(new_user_model)
     }
 
+    #[instrument(level = "debug", skip(mm))]
     pub async fn user_id_by_public_id(public_id: PublicId, mm: ModelManager) -> Result<i32> {
         // First, check if the user is already in the cache
         if let Some(cached_user_id) = ID_MAP_CACHE.get(&public_id) {
@@ -109,6 +159,7 @@ pub mod helpers {
         Ok(id)
     }
 
+    #[instrument(level = "debug", skip(mm))]
     pub async fn user_by_public_id(public_id: PublicId, mm: ModelManager) -> Result<Model> {
         let user = Entity::find()
             .filter(Column::PublicId.eq(public_id.clone()))
@@ -119,6 +170,139 @@ pub mod helpers {
         Ok(user)
     }
 
+    /// The user, only when they belong to the plan
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn user_in_plan(
+        plan_id: i32,
+        public_id: PublicId,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let user = user_by_public_id(public_id.clone(), mm).await?;
+        if user.plan_id != plan_id {
+            return Err(Error::EntityNotFound(public_id.to_string()));
+        }
+
+        Ok(user)
+    }
+
+    /// Marks a user of the plan as required or optional
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn set_required(
+        plan_public_id: PublicId,
+        user_public_id: PublicId,
+        required: bool,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+        let user = user_by_public_id(user_public_id.clone(), mm.clone()).await?;
+        if user.plan_id != plan_id {
+            return Err(Error::EntityNotFound(user_public_id.to_string()));
+        }
+
+        let mut user = user.into_active_model();
+        user.required = Set(required);
+        let user = user.update(mm.db()).await?;
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(user)
+    }
+
+    /// Sets a new address that still needs to be confirmed
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn set_email(
+        user_public_id: PublicId,
+        email: EmailAddress,
+        locale: String,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let mut user = user_by_public_id(user_public_id, mm.clone())
+            .await?
+            .into_active_model();
+        user.email = Set(Some(email));
+        user.email_token = Set(Some(Secret::default()));
+        user.email_confirmed = Set(false);
+        user.email_notify = Set(true);
+        user.email_locale = Set(Some(locale));
+
+        Ok(user.update(mm.db()).await?)
+    }
+
+    /// Confirms the address, only with the token from the confirmation email
+    #[instrument(level = "debug", skip(mm, token))]
+    pub async fn confirm_email(
+        user_public_id: PublicId,
+        token: &str,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let user = user_with_email_token(user_public_id, token, mm.clone()).await?;
+
+        let mut user = user.into_active_model();
+        user.email_confirmed = Set(true);
+        // Only responses from here on go in the digests
+        user.digest_sent_at = Set(Some(time::OffsetDateTime::now_utc()));
+
+        Ok(user.update(mm.db()).await?)
+    }
+
+    /// Stops all notifications, only with the token from one of the emails
+    #[instrument(level = "debug", skip(mm, token))]
+    pub async fn unsubscribe(
+        user_public_id: PublicId,
+        token: &str,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let user = user_with_email_token(user_public_id, token, mm.clone()).await?;
+
+        let mut user = user.into_active_model();
+        user.email_notify = Set(false);
+
+        Ok(user.update(mm.db()).await?)
+    }
+
+    async fn user_with_email_token(
+        user_public_id: PublicId,
+        token: &str,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let user = user_by_public_id(user_public_id.clone(), mm).await?;
+
+        // A wrong token looks the same as a missing user
+        match &user.email_token {
+            Some(email_token) if email_token.matches(token) => Ok(user),
+            _ => Err(Error::EntityNotFound(user_public_id.to_string())),
+        }
+    }
+
+    /// Users that receive notifications, optionally only of one plan
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn notified_users(plan_id: Option<i32>, mm: ModelManager) -> Result<Vec<Model>> {
+        let mut query = Entity::find()
+            .filter(Column::Email.is_not_null())
+            .filter(Column::EmailConfirmed.eq(true))
+            .filter(Column::EmailNotify.eq(true));
+        if let Some(plan_id) = plan_id {
+            query = query.filter(Column::PlanId.eq(plan_id));
+        }
+
+        Ok(query.all(mm.db()).await?)
+    }
+
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn set_digest_sent_at(
+        user_id: i32,
+        digest_sent_at: time::OffsetDateTime,
+        mm: ModelManager,
+    ) -> Result<()> {
+        Entity::update_many()
+            .col_expr(Column::DigestSentAt, Expr::value(digest_sent_at))
+            .filter(Column::Id.eq(user_id))
+            .exec(mm.db())
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(mm))]
     pub async fn get_users_with_date_for_plan_public_id(
         plan_public_id: PublicId,
         mm: ModelManager,
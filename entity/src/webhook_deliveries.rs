@@ -0,0 +1,126 @@
+//! Log of the attempts to deliver events to webhooks
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "webhook_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    /// Starts at 1, retries count up
+    pub attempt: i32,
+    /// `None` when there was no response at all
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+impl Model {
+    pub fn is_success(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| (200..300).contains(&status_code))
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhooks::Entity",
+        from = "Column::WebhookId",
+        to = "super::webhooks::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Webhooks,
+}
+
+impl Related<super::webhooks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhooks.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug, Clone)]
+pub struct NewWebhookDelivery {
+    pub webhook_id: i32,
+    pub event: String,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl IntoActiveModel<ActiveModel> for NewWebhookDelivery {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            webhook_id: Set(self.webhook_id),
+            event: Set(self.event),
+            attempt: Set(self.attempt),
+            status_code: Set(self.status_code),
+            // Keep whatever the endpoint or the network said within the column
+            error: Set(self
+                .error
+                .map(|error| error.chars().take(MAX_ERROR_LEN).collect())),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
+
+const MAX_ERROR_LEN: usize = 512;
+
+/// Older deliveries of a webhook are pruned
+pub const MAX_DELIVERIES_PER_WEBHOOK: u64 = 50;
+
+// region:	  --- Helpers
+pub mod helpers {
+    use super::{Column, Entity, Model, NewWebhookDelivery, MAX_DELIVERIES_PER_WEBHOOK};
+    use crate::{db::ModelManager, error::Result};
+
+    use sea_orm::{prelude::*, IntoActiveModel, QueryOrder, QuerySelect};
+    use tracing::instrument;
+
+    /// Logs an attempt and prunes the oldest ones
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn log_delivery(delivery: NewWebhookDelivery, mm: ModelManager) -> Result<()> {
+        let webhook_id = delivery.webhook_id;
+        delivery.into_active_model().insert(mm.db()).await?;
+
+        let oldest_kept = Entity::find()
+            .filter(Column::WebhookId.eq(webhook_id))
+            .order_by_desc(Column::Id)
+            .offset(MAX_DELIVERIES_PER_WEBHOOK - 1)
+            .one(mm.db())
+            .await?;
+        if let Some(oldest_kept) = oldest_kept {
+            Entity::delete_many()
+                .filter(Column::WebhookId.eq(webhook_id))
+                .filter(Column::Id.lt(oldest_kept.id))
+                .exec(mm.db())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The latest attempts for each of the webhooks, newest first
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn recent_deliveries(
+        webhook_ids: Vec<i32>,
+        limit: u64,
+        mm: ModelManager,
+    ) -> Result<Vec<Model>> {
+        let deliveries = Entity::find()
+            .filter(Column::WebhookId.is_in(webhook_ids))
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .all(mm.db())
+            .await?;
+
+        Ok(deliveries)
+    }
+}
+// endregion: --- Helpers
@@ -0,0 +1,158 @@
+//! Endpoints that receive the events of a plan, registered by its owner
+
+use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+
+use crate::types::{PublicId, Secret, WebhookUrl};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "webhooks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub public_id: PublicId,
+    pub plan_id: i32,
+    pub url: WebhookUrl,
+    /// Signs the payloads, so the endpoint can check they come from us
+    pub secret: Secret,
+    pub ctime: TimeDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::webhook_deliveries::Entity")]
+    WebhookDeliveries,
+    #[sea_orm(
+        belongs_to = "super::plans::Entity",
+        from = "Column::PlanId",
+        to = "super::plans::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Plans,
+}
+
+impl Related<super::webhook_deliveries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WebhookDeliveries.def()
+    }
+}
+
+impl Related<super::plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Plans.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub struct NewWebhook {
+    plan_id: i32,
+    url: WebhookUrl,
+}
+
+impl NewWebhook {
+    pub fn new(plan_id: i32, url: WebhookUrl) -> Self {
+        NewWebhook { plan_id, url }
+    }
+}
+
+impl IntoActiveModel<ActiveModel> for NewWebhook {
+    fn into_active_model(self) -> ActiveModel {
+        ActiveModel {
+            public_id: Set(PublicId::default()),
+            plan_id: Set(self.plan_id),
+            url: Set(self.url),
+            secret: Set(Secret::default()),
+            ctime: Set(time::OffsetDateTime::now_utc()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Maximum number of webhooks a single plan can have
+pub const MAX_WEBHOOKS_PER_PLAN: u64 = 5;
+
+// region:	  --- Helpers
+pub mod helpers {
+    use super::{Column, Entity, Model, NewWebhook, MAX_WEBHOOKS_PER_PLAN};
+    use crate::{
+        db::ModelManager,
+        error::{Error, Result},
+        plans::{self, helpers::plan_id_by_public_id},
+        types::{PublicId, WebhookUrl},
+    };
+
+    use sea_orm::{prelude::*, IntoActiveModel, QueryOrder};
+    use tracing::instrument;
+
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn create_webhook(
+        plan_public_id: PublicId,
+        url: WebhookUrl,
+        mm: ModelManager,
+    ) -> Result<Model> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        // -- Check the webhook limit, holding the plan's row so concurrent adds wait
+        let txn = plans::helpers::lock_plan(plan_id, &mm).await?;
+        let webhook_count = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            .count(&txn)
+            .await?;
+        if webhook_count >= MAX_WEBHOOKS_PER_PLAN {
+            return Err(Error::LimitReached {
+                what: "webhooks per plan",
+                max: MAX_WEBHOOKS_PER_PLAN,
+            });
+        }
+
+        let webhook = NewWebhook::new(plan_id, url)
+            .into_active_model()
+            .insert(&txn)
+            .await?;
+        txn.commit().await?;
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(webhook)
+    }
+
+    /// Only deletes the webhook when it belongs to the plan
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn delete_webhook(
+        plan_public_id: PublicId,
+        webhook_public_id: PublicId,
+        mm: ModelManager,
+    ) -> Result<()> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        let deleted = Entity::delete_many()
+            .filter(Column::PlanId.eq(plan_id))
+            .filter(Column::PublicId.eq(webhook_public_id.clone()))
+            .exec(mm.db())
+            .await?;
+        if deleted.rows_affected == 0 {
+            return Err(Error::EntityNotFound(webhook_public_id.to_string()));
+        }
+        plans::helpers::touch_plan(plan_id, mm).await?;
+
+        Ok(())
+    }
+
+    /// The plan's webhooks, oldest first
+    #[instrument(level = "debug", skip(mm))]
+    pub async fn webhooks_for_plan_public_id(
+        plan_public_id: PublicId,
+        mm: ModelManager,
+    ) -> Result<Vec<Model>> {
+        let plan_id = plan_id_by_public_id(plan_public_id, mm.clone()).await?;
+
+        let webhooks = Entity::find()
+            .filter(Column::PlanId.eq(plan_id))
+            .order_by_asc(Column::Ctime)
+            .all(mm.db())
+            .await?;
+
+        Ok(webhooks)
+    }
+}
+// endregion: --- Helpers
@@ -3,6 +3,17 @@ pub use sea_orm_migration::prelude::*;
 mod m20240918_104347_create_plans;
 mod m20240918_110512_create_users;
 mod m20240918_111732_create_dates;
+mod m20241019_090000_add_plans_mtime;
+mod m20241020_090000_add_plans_calendar_settings;
+mod m20241021_090000_add_plans_time_zone;
+mod m20241022_090000_add_owner_and_required_users;
+mod m20241023_090000_add_plans_quorum;
+mod m20241024_090000_create_webhooks;
+mod m20241025_090000_add_final_date_and_user_emails;
+mod m20241026_090000_create_comments;
+mod m20241027_090000_create_polls;
+mod m20241028_090000_add_plans_window_start;
+mod m20241029_090000_add_users_email_locale;
 
 pub struct Migrator;
 
@@ -13,6 +24,17 @@ impl MigratorTrait for Migrator {
             Box::new(m20240918_104347_create_plans::Migration),
             Box::new(m20240918_110512_create_users::Migration),
             Box::new(m20240918_111732_create_dates::Migration),
+            Box::new(m20241019_090000_add_plans_mtime::Migration),
+            Box::new(m20241020_090000_add_plans_calendar_settings::Migration),
+            Box::new(m20241021_090000_add_plans_time_zone::Migration),
+            Box::new(m20241022_090000_add_owner_and_required_users::Migration),
+            Box::new(m20241023_090000_add_plans_quorum::Migration),
+            Box::new(m20241024_090000_create_webhooks::Migration),
+            Box::new(m20241025_090000_add_final_date_and_user_emails::Migration),
+            Box::new(m20241026_090000_create_comments::Migration),
+            Box::new(m20241027_090000_create_polls::Migration),
+            Box::new(m20241028_090000_add_plans_window_start::Migration),
+            Box::new(m20241029_090000_add_users_email_locale::Migration),
         ]
     }
 }
@@ -0,0 +1,38 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    Mtime,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Last time anything in the plan changed, used for caching
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .add_column(
+                        timestamp_with_time_zone(Plans::Mtime).default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .drop_column(Plans::Mtime)
+                    .to_owned(),
+            )
+            .await
+    }
+}
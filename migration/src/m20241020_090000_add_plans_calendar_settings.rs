@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    WeekStart,
+    ShowWeekNumbers,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // No week start means the default of the viewer's locale
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .add_column(string_len_null(Plans::WeekStart, 16))
+                    .add_column(boolean(Plans::ShowWeekNumbers).default(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .drop_column(Plans::WeekStart)
+                    .drop_column(Plans::ShowWeekNumbers)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    Id,
+    MinAttendance,
+}
+
+#[derive(DeriveIden)]
+enum QuorumDates {
+    Table,
+    Id,
+    PlanId,
+    Date,
+    Ctime,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // No minimum by default
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .add_column(integer_null(Plans::MinAttendance))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Dates that reached the quorum once, so it is only announced the first time
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuorumDates::Table)
+                    .if_not_exists()
+                    .col(pk_auto(QuorumDates::Id))
+                    .col(integer(QuorumDates::PlanId))
+                    .col(date(QuorumDates::Date))
+                    .col(timestamp_with_time_zone(QuorumDates::Ctime))
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK plan_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(QuorumDates::Table, QuorumDates::PlanId)
+                    .to(Plans::Table, Plans::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Unique dates per plan
+        manager
+            .create_index(
+                Index::create()
+                    .table(QuorumDates::Table)
+                    .col(QuorumDates::PlanId)
+                    .col(QuorumDates::Date)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QuorumDates::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .drop_column(Plans::MinAttendance)
+                    .to_owned(),
+            )
+            .await
+    }
+}
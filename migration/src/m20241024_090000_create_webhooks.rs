@@ -0,0 +1,113 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Webhooks {
+    Table,
+    Id,
+    PublicId,
+    PlanId,
+    Url,
+    Secret,
+    Ctime,
+}
+
+#[derive(DeriveIden)]
+enum WebhookDeliveries {
+    Table,
+    Id,
+    WebhookId,
+    Event,
+    Attempt,
+    StatusCode,
+    Error,
+    Ctime,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhooks::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Webhooks::Id))
+                    .col(string_len_uniq(Webhooks::PublicId, 32))
+                    .col(integer(Webhooks::PlanId))
+                    .col(string_len(Webhooks::Url, 2048))
+                    .col(string_len(Webhooks::Secret, 32))
+                    .col(timestamp_with_time_zone(Webhooks::Ctime))
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK plan_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(Webhooks::Table, Webhooks::PlanId)
+                    .to(Plans::Table, Plans::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every attempt to deliver an event, so owners can see why their endpoint misses events
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDeliveries::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WebhookDeliveries::Id))
+                    .col(integer(WebhookDeliveries::WebhookId))
+                    .col(string_len(WebhookDeliveries::Event, 64))
+                    .col(integer(WebhookDeliveries::Attempt))
+                    .col(integer_null(WebhookDeliveries::StatusCode))
+                    .col(string_len_null(WebhookDeliveries::Error, 512))
+                    .col(timestamp_with_time_zone(WebhookDeliveries::Ctime))
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK webhook_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(WebhookDeliveries::Table, WebhookDeliveries::WebhookId)
+                    .to(Webhooks::Table, Webhooks::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The log is read newest first per webhook
+        manager
+            .create_index(
+                Index::create()
+                    .table(WebhookDeliveries::Table)
+                    .col(WebhookDeliveries::WebhookId)
+                    .col(WebhookDeliveries::Ctime)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDeliveries::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Webhooks::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    FinalDate,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Email,
+    EmailToken,
+    EmailConfirmed,
+    EmailNotify,
+    DigestSentAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Set by the owner once the plan is decided
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .add_column(date_null(Plans::FinalDate))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Emails are optional, only confirmed addresses receive notifications
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(string_len_null(Users::Email, 254))
+                    .add_column(string_len_null(Users::EmailToken, 32))
+                    .add_column(boolean(Users::EmailConfirmed).default(false))
+                    .add_column(boolean(Users::EmailNotify).default(true))
+                    .add_column(timestamp_with_time_zone_null(Users::DigestSentAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Email)
+                    .drop_column(Users::EmailToken)
+                    .drop_column(Users::EmailConfirmed)
+                    .drop_column(Users::EmailNotify)
+                    .drop_column(Users::DigestSentAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Plans::Table)
+                    .drop_column(Plans::FinalDate)
+                    .to_owned(),
+            )
+            .await
+    }
+}
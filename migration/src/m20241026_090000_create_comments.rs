@@ -0,0 +1,89 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Comments {
+    Table,
+    Id,
+    PublicId,
+    PlanId,
+    UserId,
+    Date,
+    Text,
+    Ctime,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The author and the date are optional, a comment can be about the plan as a whole
+        manager
+            .create_table(
+                Table::create()
+                    .table(Comments::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Comments::Id))
+                    .col(string_len_uniq(Comments::PublicId, 32))
+                    .col(integer(Comments::PlanId))
+                    .col(integer_null(Comments::UserId))
+                    .col(date_null(Comments::Date))
+                    .col(string_len(Comments::Text, 500))
+                    .col(timestamp_with_time_zone(Comments::Ctime))
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK plan_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(Comments::Table, Comments::PlanId)
+                    .to(Plans::Table, Plans::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK user_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(Comments::Table, Comments::UserId)
+                    .to(Users::Table, Users::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The thread is read in order per plan
+        manager
+            .create_index(
+                Index::create()
+                    .table(Comments::Table)
+                    .col(Comments::PlanId)
+                    .col(Comments::Ctime)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Comments::Table).to_owned())
+            .await
+    }
+}
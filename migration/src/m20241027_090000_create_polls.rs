@@ -0,0 +1,159 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Plans {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum PollQuestions {
+    Table,
+    Id,
+    PublicId,
+    PlanId,
+    Text,
+    Ctime,
+}
+
+#[derive(DeriveIden)]
+enum PollOptions {
+    Table,
+    Id,
+    PublicId,
+    QuestionId,
+    Text,
+    Ctime,
+}
+
+#[derive(DeriveIden)]
+enum PollVotes {
+    Table,
+    Id,
+    OptionId,
+    UserId,
+    Vote,
+    Ctime,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Questions besides the date, like where to go
+        manager
+            .create_table(
+                Table::create()
+                    .table(PollQuestions::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PollQuestions::Id))
+                    .col(string_len_uniq(PollQuestions::PublicId, 32))
+                    .col(integer(PollQuestions::PlanId))
+                    .col(string_len(PollQuestions::Text, 256))
+                    .col(timestamp_with_time_zone(PollQuestions::Ctime))
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK plan_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(PollQuestions::Table, PollQuestions::PlanId)
+                    .to(Plans::Table, Plans::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PollOptions::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PollOptions::Id))
+                    .col(string_len_uniq(PollOptions::PublicId, 32))
+                    .col(integer(PollOptions::QuestionId))
+                    .col(string_len(PollOptions::Text, 128))
+                    .col(timestamp_with_time_zone(PollOptions::Ctime))
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK question_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(PollOptions::Table, PollOptions::QuestionId)
+                    .to(PollQuestions::Table, PollQuestions::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // One vote per user and option
+        manager
+            .create_table(
+                Table::create()
+                    .table(PollVotes::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PollVotes::Id))
+                    .col(integer(PollVotes::OptionId))
+                    .col(integer(PollVotes::UserId))
+                    .col(string_len(PollVotes::Vote, 8))
+                    .col(timestamp_with_time_zone(PollVotes::Ctime))
+                    .index(
+                        Index::create()
+                            .unique()
+                            .col(PollVotes::OptionId)
+                            .col(PollVotes::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK option_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(PollVotes::Table, PollVotes::OptionId)
+                    .to(PollOptions::Table, PollOptions::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // FK user_id
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(PollVotes::Table, PollVotes::UserId)
+                    .to(Users::Table, Users::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PollVotes::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PollOptions::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PollQuestions::Table).to_owned())
+            .await
+    }
+}
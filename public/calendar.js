@@ -0,0 +1,84 @@
+// Alpine components of the calendar, registered by name so the pages need no inline expressions
+
+document.addEventListener("alpine:init", () => {
+  // Dragging across dates picks the whole range, a drag that starts on a picked date clears it
+  Alpine.data("dragRange", () => ({
+    from: null,
+    to: null,
+    clear: false,
+    dragging: false,
+
+    start(event) {
+      const date = event.target.closest("[data-date]");
+      if (!date) return;
+
+      this.from = this.to = date.dataset.date;
+      this.clear = date.dataset.picked === "true";
+      this.dragging = true;
+      this.highlight(event.currentTarget);
+    },
+
+    extend(event) {
+      const date = event.target.closest("[data-date]")?.dataset.date;
+      if (!this.dragging || !date) return;
+
+      this.to = date;
+      this.highlight(event.currentTarget);
+    },
+
+    finish(event) {
+      // A drag that stays on one date is a click, which the date handles itself
+      if (this.dragging && this.from !== this.to) {
+        this.$nextTick(() => htmx.trigger(this.$refs.range, "submit"));
+      }
+      this.cancel(event);
+    },
+
+    cancel(event) {
+      this.dragging = false;
+      this.highlight(event.currentTarget);
+    },
+
+    inRange(date) {
+      const [first, last] = [this.from, this.to].sort();
+      return this.dragging && first <= date && date <= last;
+    },
+
+    highlight(calendar) {
+      for (const cell of calendar.querySelectorAll("[data-date]")) {
+        cell.classList.toggle("bg-highlight", this.inRange(cell.dataset.date));
+      }
+    },
+  }));
+
+  // Arrow keys move between the dates of a month, Home and End to the ends of the week
+  Alpine.data("calendarGrid", () => ({
+    move(event) {
+      const cells = [...event.currentTarget.querySelectorAll("[data-cell]")];
+      const index = cells.indexOf(document.activeElement);
+      const row = index - (index % 7);
+      const next = {
+        ArrowLeft: index - 1,
+        ArrowRight: index + 1,
+        ArrowUp: index - 7,
+        ArrowDown: index + 7,
+        Home: row,
+        End: row + 6,
+      }[event.key];
+
+      if (index >= 0 && next !== undefined && cells[next]) {
+        event.preventDefault();
+        cells[next].focus();
+      }
+    },
+
+    // Only the last focused date is in the tab order, so tab leaves the grid
+    rove(event) {
+      if (!event.target.hasAttribute("data-cell")) return;
+
+      for (const cell of event.currentTarget.querySelectorAll("[data-cell]")) {
+        cell.tabIndex = cell === event.target ? 0 : -1;
+      }
+    },
+  }));
+});
@@ -10,94 +10,148 @@ use http::StatusCode;
 use leptos::prelude::*;
 use tracing::debug;
 
+use crate::{
+    fileserv::asset_url,
+    htmx_helpers::Htmx,
+    i18n::{t, use_locale, Locale, Text},
+    middleware::{mw_csrf::CSRF_HEADER, mw_time_zone::TIME_ZONE_COOKIE},
+    plan_page::PLAN_ROUTE,
+    request_context::RequestContext,
+    theme::{use_theme, Theme},
+    util_components::{CsrfField, ErrorMessage, ERRORS_ID},
+};
+
 pub fn routes(mm: ModelManager) -> Router {
     Router::new().route("/", get(index_page)).with_state(mm)
 }
 
-pub async fn index_page() -> Html<String> {
+pub async fn index_page(context: RequestContext) -> Html<String> {
     debug!("{:<12} - index", "HANDLER");
 
-    let content = view! {
-        <Page title="Pick The Day!".to_string()>
-            <HomePage/>
-        </Page>
-    }
-    .to_html();
+    let content = context.render(|| {
+        view! {
+            <Page title=t(Text::PageTitle).to_string()>
+                <HomePage/>
+            </Page>
+        }
+    });
 
     Html(content)
 }
 
-pub async fn not_found_page() -> impl IntoResponse {
-    let content = view! {
-        <Page title="Pick The Day!".to_string()>
-            <NotFound/>
-        </Page>
-    }
-    .to_html();
+pub async fn not_found_page(context: RequestContext) -> impl IntoResponse {
+    let content = context.render(|| {
+        view! {
+            <Page title=t(Text::PageTitle).to_string()>
+                <NotFound/>
+            </Page>
+        }
+    });
 
     (StatusCode::NOT_FOUND, Html(content)).into_response()
 }
 
 #[component]
 pub fn Page(title: String, children: Children) -> impl IntoView {
+    // htmx sends the CSRF token along with every request made from within the body
+    let hx_headers = use_context::<RequestContext>().map(|context| {
+        format!(
+            r#"{{"{}": "{}"}}"#,
+            CSRF_HEADER.as_str(),
+            context.csrf_token
+        )
+    });
+
     view! {
-        <!DOCTYPE html>
-        <html lang="en">
+        <Document>
             <head>
                 <meta name="viewport" content="width=device-width, initial-scale=1.0"/>
-                <meta name="color-scheme" content="dark"/>
+                <meta name="color-scheme" content=use_theme().color_scheme()/>
                 <meta charset="utf-8"/>
 
                 <title>{title}</title>
 
-                <link href="/main.css" type="text/css" rel="stylesheet"/>
+                // htmx would otherwise inject an inline style that the CSP blocks, and nothing
+                // needs its eval
+                <meta
+                    name="htmx-config"
+                    content=r#"{"includeIndicatorStyles": false, "allowEval": false}"#
+                />
+
+                <link href=asset_url("main.css") type="text/css" rel="stylesheet"/>
 
-                <script src="https://unpkg.com/htmx.org@2.0.2/dist/htmx.min.js" defer></script>
-                <script src="https://unpkg.com/alpinejs@3.14.1/dist/cdn.min.js" defer></script>
+                <script src=asset_url("vendor/htmx.min.js") defer></script>
+                // Registers its components before Alpine starts
+                <script src=asset_url("calendar.js") defer></script>
+                <script src=asset_url("vendor/alpine-csp.min.js") defer></script>
 
-                <CopyInputToClipboardScript/>
+                <CopyToClipboardScript/>
+                <HtmxErrorSwapScript/>
+                <TimeZoneDetectScript/>
             </head>
 
-            <body class="bg-slate-800">
+            <body class="bg-surface" hx-headers=hx_headers>
 
-                <main class="container relative mx-auto  text-white text-center pt-16">
+                <main class="container relative mx-auto  text-content text-center pt-16">
+                    <div id=ERRORS_ID.to_string() aria-live="polite"></div>
                     {children()}
                 </main>
 
+                <LocalePicker/>
+                <ThemePicker/>
+
             </body>
+        </Document>
+    }
+}
+
+/// The `<html>` element, in the language and theme of the request
+#[component]
+fn Document(children: Children) -> impl IntoView {
+    view! {
+        <!DOCTYPE html>
+        <html lang=use_locale().to_string() data-theme=use_theme().data_theme()>
+            {children()}
         </html>
     }
 }
 
 #[component]
 fn AlpineGlobalState() -> impl IntoView {
-    view! { <script>"document.addEventListener('alpine:init', () => {Alpine.store({})})"</script> }
+    view! {
+        <script nonce=csp_nonce()>
+            "document.addEventListener('alpine:init', () => {Alpine.store({})})"
+        </script>
+    }
 }
 
 #[component]
 fn HomePage() -> impl IntoView {
     view! {
         <div>
-            <h1>"Pick The Day"</h1>
-            <p>"Create a meetup!"</p>
+            <h1>{t(Text::AppName)}</h1>
+            <p>{t(Text::HomeTagline)}</p>
 
             <form
-                hx-post="/plan"
+                method="post"
+                action=PLAN_ROUTE.to_string()
+                {..Htmx::post(PLAN_ROUTE).attrs()}
                 class="container relative z-0 mx-auto flex max-w-80 justify-center space-x-4"
             >
+                <CsrfField/>
                 <div>
                     <input
                         type="text"
                         name="plan_name"
-                        class="border-1 peer block w-full appearance-none rounded-lg border border-gray-600 bg-transparent px-2 py-2.5 text-sm text-white outline-none focus:border-gray-300 "
-                        placeholder="e.g. Tennis"
+                        class="border-1 peer block w-full appearance-none rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content outline-none focus:border-content "
+                        placeholder=t(Text::PlanNamePlaceholder)
                     />
                 </div>
                 <button
                     type="submit"
-                    class="mb-2 me-2 flex rounded-lg border-gray-700 bg-gray-600 px-5 py-2.5 text-sm font-medium text-white hover:bg-gray-700"
+                    class="mb-2 me-2 flex rounded-lg border-line bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
                 >
-                    "Create"
+                    {t(Text::Create)}
                 </button>
             </form>
         </div>
@@ -108,35 +162,222 @@ fn HomePage() -> impl IntoView {
 pub fn NotFound() -> impl IntoView {
     view! {
         <div>
-            <h1>"Not Found"</h1>
-            <p>"Page not found"</p>
-            <a href="/">"Back to Home"</a>
+            <h1>{t(Text::NotFoundTitle)}</h1>
+            <p>{t(Text::NotFoundMessage)}</p>
+            <a href="/">{t(Text::BackToHome)}</a>
         </div>
     }
 }
 
+/// An error of a request made without htmx, with the way back to where it came from
+#[component]
+pub fn ErrorPage(message: String, back: String) -> impl IntoView {
+    view! {
+        <div>
+            <ErrorMessage message=message/>
+            <a href=back class="underline">
+                {t(Text::GoBack)}
+            </a>
+        </div>
+    }
+}
+
+/// Links that switch the language of the current page, see `mw_locale`
+#[component]
+fn LocalePicker() -> impl IntoView {
+    let current_locale = use_locale();
+    let query = use_context::<RequestContext>().and_then(|context| context.query);
+
+    view! {
+        <nav aria-label=t(Text::Language) class="my-8 flex justify-center space-x-4 text-sm">
+            {Locale::ALL
+                .into_iter()
+                .map(|locale| {
+                    let class = if locale == current_locale {
+                        "text-content font-bold"
+                    } else {
+                        "text-muted hover:text-content"
+                    };
+
+                    view! {
+                        <a
+                            href=query_with(query.as_deref(), "lang", &locale.to_string())
+                            hreflang=locale.to_string()
+                            class=class
+                        >
+                            {locale.native_name()}
+                        </a>
+                    }
+                })
+                .collect_view()}
+        </nav>
+    }
+}
+
+/// Links that switch the theme of the current page, see `mw_theme`
+#[component]
+fn ThemePicker() -> impl IntoView {
+    let current_theme = use_theme();
+    let query = use_context::<RequestContext>().and_then(|context| context.query);
+
+    view! {
+        <nav aria-label=t(Text::Theme) class="my-8 flex justify-center space-x-4 text-sm">
+            {Theme::ALL
+                .into_iter()
+                .map(|theme| {
+                    let class = if theme == current_theme {
+                        "text-content font-bold"
+                    } else {
+                        "text-muted hover:text-content"
+                    };
+
+                    view! {
+                        <a
+                            href=query_with(query.as_deref(), "theme", &theme.to_string())
+                            aria-current=(theme == current_theme).then_some("true")
+                            class=class
+                        >
+                            {theme.name()}
+                        </a>
+                    }
+                })
+                .collect_view()}
+        </nav>
+    }
+}
+
+/// The query with `name` set to `value`, so switching keeps the page as it was
+fn query_with(query: Option<&str>, name: &str, value: &str) -> String {
+    let mut params: Vec<(String, String)> =
+        serde_urlencoded::from_str(query.unwrap_or_default()).unwrap_or_default();
+    params.retain(|(param, _)| param != name);
+    params.push((name.to_string(), value.to_string()));
+
+    format!(
+        "?{}",
+        serde_urlencoded::to_string(params).unwrap_or_default()
+    )
+}
+
+/// Copies the `data-copy-to-clipboard` value of any clicked element, see `CopyToClipboard`
 #[component]
-fn CopyInputToClipboardScript() -> impl IntoView {
+fn CopyToClipboardScript() -> impl IntoView {
     view! {
-        <script>
+        <script nonce=csp_nonce()>
             "
-            function initClipboard(copyButtonId, textToCopy) {
-                const copyButton = document.getElementById(copyButtonId);
-            
+            document.addEventListener('click', async (event) => {
+                const copyButton = event.target.closest('[data-copy-to-clipboard]');
                 if (!copyButton) {
-                    console.error('Invalid input or copy button element');
                     return;
                 }
-            
-                copyButton.addEventListener('click', async () => {
-                    try {
-                        await navigator.clipboard.writeText(textToCopy);
-                    } catch (err) {
-                        console.error('Failed to copy: ', err);
-                    }
-                });
-            }
+
+                try {
+                    await navigator.clipboard.writeText(copyButton.dataset.copyToClipboard);
+                } catch (err) {
+                    console.error('Failed to copy: ', err);
+                }
+            });
+            "
+        </script>
+    }
+}
+
+#[component]
+fn HtmxErrorSwapScript() -> impl IntoView {
+    view! {
+        <script nonce=csp_nonce()>
+            "
+            document.addEventListener('htmx:beforeSwap', (event) => {
+                // Error fragments are retargeted to the error area by the server, so swap them anyway
+                if (event.detail.xhr.getResponseHeader('HX-Retarget')) {
+                    event.detail.shouldSwap = true;
+                    event.detail.isError = false;
+                }
+            });
             "
         </script>
     }
 }
+
+/// Reports the browser's time zone in a cookie, so "today" is computed in the viewer's zone
+#[component]
+fn TimeZoneDetectScript() -> impl IntoView {
+    let script = format!(
+        "
+        (() => {{
+            const timeZone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+            const cookie = '{TIME_ZONE_COOKIE}=' + timeZone;
+            if (timeZone && !document.cookie.split('; ').includes(cookie)) {{
+                document.cookie = cookie + '; path=/; max-age=31536000; samesite=lax';
+            }}
+        }})();
+        "
+    );
+
+    view! { <script nonce=csp_nonce()>{script}</script> }
+}
+
+/// Nonce that allows inline scripts under the Content-Security-Policy
+fn csp_nonce() -> Option<String> {
+    use_context::<RequestContext>().map(|context| context.csp_nonce.to_string())
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+
+    use crate::{i18n::Locale, test_support::fx_context};
+
+    #[test]
+    fn test_document_in_request_theme() -> Result<()> {
+        let mut fx_context = fx_context();
+        fx_context.locale = Locale::Nl;
+        fx_context.theme = Theme::HighContrast;
+
+        let html = fx_context.clone().render(|| {
+            view! {
+                <Document>
+                    <body></body>
+                </Document>
+            }
+        });
+        assert!(html.contains(r#"<html lang="nl" data-theme="contrast">"#));
+
+        // -- The system theme is left to the media queries
+        fx_context.theme = Theme::System;
+        let html = fx_context.render(|| {
+            view! {
+                <Document>
+                    <body></body>
+                </Document>
+            }
+        });
+        assert!(!html.contains("data-theme"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pickers_keep_the_query() -> Result<()> {
+        let mut fx_context = fx_context();
+        fx_context.query = Some("month=November&year=2026&user=abc&lang=de".to_string());
+
+        let html = fx_context.render(|| {
+            view! {
+                <LocalePicker/>
+                <ThemePicker/>
+            }
+        });
+
+        assert!(html.contains(r#"href="?month=November&amp;year=2026&amp;user=abc&amp;lang=fr""#));
+        assert!(html.contains(
+            r#"href="?month=November&amp;year=2026&amp;user=abc&amp;lang=de&amp;theme=dark""#
+        ));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
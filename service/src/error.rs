@@ -1,10 +1,18 @@
 use std::sync::Arc;
 
-use axum::response::{IntoResponse, Response};
-use http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum_htmx::{HxReswap, HxRetarget, SwapOption};
+use http::{header::RETRY_AFTER, StatusCode};
+use leptos::prelude::*;
 use thiserror::Error;
 use tracing::error;
 
+use crate::{
+    htmx_helpers::HtmxTarget,
+    i18n::{Locale, Text},
+    util_components::{ErrorMessage, ERRORS_ID},
+};
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Error, Debug)]
@@ -14,12 +22,26 @@ pub enum Error {
     NewPlanInvalid(String),
     #[error("Invalid user: {0}")]
     NewUserInvalid(String),
+    #[error("Invalid poll: {0}")]
+    PollInvalid(String),
     #[error("Invalid uri: {0}")]
     UriInvalid(#[from] http::uri::InvalidUri),
 
+    // -- Abuse protection
+    #[error("Too many requests, retry after {retry_after_secs}s")]
+    RateLimited { retry_after_secs: u64 },
+    #[error("Request rejected: {0}")]
+    CsrfRejected(&'static str),
+
+    // -- Request
+    #[error("Request context missing: {0}")]
+    RequestContextMissing(&'static str),
+    #[error("Only the owner of the plan can do this")]
+    NotPlanOwner,
+
     // -- Entity
-    #[error("Entity not found: {0}")]
-    EntityNotFound(#[from] entity::error::Error),
+    #[error("Entity error: {0}")]
+    Entity(#[from] entity::error::Error),
 
     // -- External
     #[error("Database error: {0}")]
@@ -28,6 +50,65 @@ pub enum Error {
     Dotenvy(#[from] dotenvy::Error),
     #[error("std env error: {0}")]
     StdEnv(#[from] std::env::VarError),
+    #[error("Config value has the wrong format: {0}")]
+    ConfigWrongFormat(&'static str),
+    #[error("Email error: {0}")]
+    Email(#[from] lettre::error::Error),
+    #[error("Email address error: {0}")]
+    EmailAddress(#[from] lettre::address::AddressError),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+impl Error {
+    /// Maps the error to the status code and the message that is safe to show to the client
+    pub fn client_status_and_message(&self, locale: Locale) -> (StatusCode, String) {
+        use entity::error::Error as EntityError;
+
+        let invalid = |text, reason| {
+            let message = format!("{}: {reason}", locale.text(text));
+            (StatusCode::UNPROCESSABLE_ENTITY, message)
+        };
+        let message = |status, text| (status, locale.text(text).to_string());
+
+        match self {
+            Error::NewPlanInvalid(reason) => invalid(Text::InvalidPlan, reason),
+            Error::NewUserInvalid(reason) => invalid(Text::InvalidUser, reason),
+            Error::PollInvalid(reason) => invalid(Text::InvalidPoll, reason),
+            Error::RateLimited { .. } => {
+                message(StatusCode::TOO_MANY_REQUESTS, Text::TooManyRequests)
+            }
+            Error::CsrfRejected(_) => message(StatusCode::FORBIDDEN, Text::RequestRejected),
+            Error::NotPlanOwner => message(StatusCode::FORBIDDEN, Text::NotPlanOwner),
+            Error::Entity(EntityError::EntityNotFound(_)) => {
+                message(StatusCode::NOT_FOUND, Text::ErrorNotFound)
+            }
+            Error::Entity(EntityError::LimitReached { max, .. }) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("{} ({max})", locale.text(Text::LimitReached)),
+            ),
+            _ => message(StatusCode::INTERNAL_SERVER_ERROR, Text::SomethingWentWrong),
+        }
+    }
+
+    /// The message as a fragment that htmx swaps into the page's error area
+    pub fn fragment_response(&self, locale: Locale) -> Response {
+        let (status, message) = self.client_status_and_message(locale);
+
+        let body = Html(view! { <ErrorMessage message=message/> }.to_html());
+        let retarget = HxRetarget(HtmxTarget::from(ERRORS_ID.clone()).to_string());
+        let reswap = HxReswap(SwapOption::InnerHtml);
+
+        let mut response = (status, retarget, reswap, body).into_response();
+
+        if let Error::RateLimited { retry_after_secs } = self {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, (*retry_after_secs).into());
+        }
+
+        response
+    }
 }
 
 // region:    --- Axum IntoResponse
@@ -35,10 +116,8 @@ impl IntoResponse for Error {
     fn into_response(self) -> Response {
         error!("{:<12} - {self:?}", "INTO_RES");
 
-        // TODO! Create a mapping from entity not found to 404 etc.
-
-        // Create a placeholder Axum reponse.
-        let mut response = StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        // Without the request's locale, `mw_error_response` renders it again in the right one
+        let mut response = self.fragment_response(Locale::default());
 
         // Insert the Error into the reponse.
         response.extensions_mut().insert(Arc::new(self));
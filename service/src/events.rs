@@ -0,0 +1,87 @@
+//! Events about plans, for subsystems like notifications or webhooks to react to.
+//!
+//! Publishing never blocks a request, events are dropped when nobody subscribed.
+
+use entity::types::PublicId;
+use once_cell::sync::Lazy;
+use time::Date;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanEvent {
+    /// Someone added themselves to the plan
+    UserJoined {
+        plan_public_id: PublicId,
+        user_public_id: PublicId,
+    },
+    /// A user picked or unpicked dates, one event for a bulk change
+    AvailabilityChanged {
+        plan_public_id: PublicId,
+        user_public_id: PublicId,
+        dates: Vec<Date>,
+        available: bool,
+    },
+    /// A date reached the plan's minimum attendance for the first time
+    QuorumReached {
+        plan_public_id: PublicId,
+        date: Date,
+        available: usize,
+        min_attendance: usize,
+    },
+    /// The owner settled on a date
+    PlanFinalized {
+        plan_public_id: PublicId,
+        date: Date,
+    },
+}
+
+impl PlanEvent {
+    pub fn plan_public_id(&self) -> &PublicId {
+        match self {
+            PlanEvent::UserJoined { plan_public_id, .. }
+            | PlanEvent::AvailabilityChanged { plan_public_id, .. }
+            | PlanEvent::QuorumReached { plan_public_id, .. }
+            | PlanEvent::PlanFinalized { plan_public_id, .. } => plan_public_id,
+        }
+    }
+
+    /// Stable name of the event, as sent to webhooks
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlanEvent::UserJoined { .. } => "user_joined",
+            PlanEvent::AvailabilityChanged { .. } => "availability_changed",
+            PlanEvent::QuorumReached { .. } => "quorum_reached",
+            PlanEvent::PlanFinalized { .. } => "plan_finalized",
+        }
+    }
+}
+
+/// Subscribers that fall further behind than this miss the oldest events
+const EVENTS_CAPACITY: usize = 256;
+
+static EVENTS: Lazy<broadcast::Sender<PlanEvent>> =
+    Lazy::new(|| broadcast::channel(EVENTS_CAPACITY).0);
+
+pub fn publish(event: PlanEvent) {
+    debug!("{:<12} - publish - {event:?}", "EVENTS");
+    // Only fails without subscribers, which is fine
+    let _ = EVENTS.send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<PlanEvent> {
+    EVENTS.subscribe()
+}
+
+/// Logs every event, so they are visible without any other subscriber
+pub async fn log_events() {
+    let mut events = subscribe();
+
+    loop {
+        match events.recv().await {
+            Ok(event) => info!("{:<12} - {event:?}", "EVENTS"),
+            Err(RecvError::Lagged(missed)) => warn!("{:<12} - missed {missed} events", "EVENTS"),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
@@ -1,35 +1,64 @@
+use std::collections::HashMap;
+use std::path::Path;
+
 use axum::response::Response as AxumResponse;
 use axum::{
     body::Body,
+    extract::FromRequestParts,
     http::{Request, Response, StatusCode},
     response::IntoResponse,
 };
+use http::{
+    header::{ACCEPT_ENCODING, CACHE_CONTROL, IF_MODIFIED_SINCE},
+    HeaderValue, Uri,
+};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
 use tower::ServiceExt;
 use tower_http::services::ServeDir;
 
 use crate::app::not_found_page;
 use crate::config::web_config;
+use crate::request_context::RequestContext;
 
 pub async fn file_and_error_handler(req: Request<Body>) -> AxumResponse {
     let root = &web_config().WEB_FOLDER;
-    let (parts, _) = req.into_parts();
+    let (mut parts, _) = req.into_parts();
 
     let mut static_parts = parts.clone();
     static_parts.headers.clear();
-    if let Some(encodings) = parts.headers.get("accept-encoding") {
-        static_parts
-            .headers
-            .insert("accept-encoding", encodings.clone());
+    for header in [ACCEPT_ENCODING, IF_MODIFIED_SINCE] {
+        if let Some(value) = parts.headers.get(&header) {
+            static_parts.headers.insert(header, value.clone());
+        }
+    }
+
+    // -- Serve fingerprinted assets from their real path
+    let fingerprinted_path = ASSET_MANIFEST.real_path(parts.uri.path());
+    if let Some(real_path) = fingerprinted_path {
+        if let Ok(uri) = real_path.parse::<Uri>() {
+            static_parts.uri = uri;
+        }
     }
 
-    let res = get_static_file(Request::from_parts(static_parts, Body::empty()), root)
+    let mut res = get_static_file(Request::from_parts(static_parts, Body::empty()), root)
         .await
         .unwrap();
 
-    if res.status() == StatusCode::OK {
+    if res.status() == StatusCode::OK || res.status() == StatusCode::NOT_MODIFIED {
+        // Other files keep their name when they change, so always revalidate them
+        let cache_control = if fingerprinted_path.is_some() {
+            IMMUTABLE_CACHE_CONTROL.clone()
+        } else {
+            HeaderValue::from_static("no-cache")
+        };
+        res.headers_mut().insert(CACHE_CONTROL, cache_control);
         res.into_response()
     } else {
-        not_found_page().await.into_response()
+        match RequestContext::from_request_parts(&mut parts, &()).await {
+            Ok(context) => not_found_page(context).await.into_response(),
+            Err(err) => err.into_response(),
+        }
     }
 }
 
@@ -52,3 +81,122 @@ async fn get_static_file(
         )),
     }
 }
+
+// region:	  --- Fingerprinted assets
+
+/// Assets in the web folder that are served under a content hashed name
+const FINGERPRINTED_ASSETS: [&str; 4] = [
+    "main.css",
+    "calendar.js",
+    "vendor/htmx.min.js",
+    "vendor/alpine-csp.min.js",
+];
+
+static IMMUTABLE_CACHE_CONTROL: HeaderValue =
+    HeaderValue::from_static("public, max-age=31536000, immutable");
+
+static ASSET_MANIFEST: Lazy<AssetManifest> = Lazy::new(|| {
+    AssetManifest::load(&web_config().WEB_FOLDER, &FINGERPRINTED_ASSETS)
+        .expect("Assets are missing from the web folder, run `just vendor_update`")
+});
+
+/// Fails at startup, rather than serving pages whose scripts are missing
+pub fn init_assets() {
+    Lazy::force(&ASSET_MANIFEST);
+}
+
+/// Url of an asset in the web folder, fingerprinted when it is one of the `FINGERPRINTED_ASSETS`
+pub fn asset_url(asset: &str) -> String {
+    ASSET_MANIFEST.url(asset)
+}
+
+/// Maps assets to urls containing a hash of their content, so they can be cached forever
+#[derive(Debug, Default)]
+struct AssetManifest {
+    /// asset (`vendor/htmx.min.js`) -> url (`/vendor/htmx.min.1a2b3c4d5e6f7a8b.js`)
+    urls: HashMap<String, String>,
+    /// url -> real path (`/vendor/htmx.min.js`)
+    real_paths: HashMap<String, String>,
+}
+
+impl AssetManifest {
+    fn load(root: &str, assets: &[&str]) -> std::io::Result<Self> {
+        let mut manifest = AssetManifest::default();
+
+        for asset in assets {
+            let content = std::fs::read(Path::new(root).join(asset))
+                .map_err(|err| std::io::Error::new(err.kind(), format!("{asset}: {err}")))?;
+            manifest.insert(asset, &content);
+        }
+
+        Ok(manifest)
+    }
+
+    fn insert(&mut self, asset: &str, content: &[u8]) {
+        let hash = hex::encode(&Sha256::digest(content)[..8]);
+
+        let url = match asset.rsplit_once('.') {
+            Some((stem, extension)) => format!("/{stem}.{hash}.{extension}"),
+            None => format!("/{asset}.{hash}"),
+        };
+
+        self.real_paths.insert(url.clone(), format!("/{asset}"));
+        self.urls.insert(asset.to_string(), url);
+    }
+
+    fn url(&self, asset: &str) -> String {
+        self.urls
+            .get(asset)
+            .cloned()
+            .unwrap_or_else(|| format!("/{asset}"))
+    }
+
+    fn real_path(&self, url: &str) -> Option<&str> {
+        self.real_paths.get(url).map(String::as_str)
+    }
+}
+
+// endregion: --- Fingerprinted assets
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_asset_manifest_fingerprint() -> Result<()> {
+        let mut manifest = AssetManifest::default();
+        manifest.insert("vendor/htmx.min.js", b"htmx");
+
+        let url = manifest.url("vendor/htmx.min.js");
+
+        assert!(url.starts_with("/vendor/htmx.min."));
+        assert!(url.ends_with(".js"));
+        assert_ne!(url, "/vendor/htmx.min.js");
+        assert_eq!(manifest.real_path(&url), Some("/vendor/htmx.min.js"));
+
+        // Unknown assets are served as is
+        assert_eq!(manifest.url("favicon.ico"), "/favicon.ico");
+        assert_eq!(manifest.real_path("/favicon.ico"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_asset_manifest_missing_asset() -> Result<()> {
+        let fx_root = std::env::temp_dir().join("picktheday-no-assets");
+
+        let err = AssetManifest::load(
+            fx_root.to_str().unwrap_or_default(),
+            &["vendor/htmx.min.js"],
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("vendor/htmx.min.js"));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -1,4 +1,6 @@
+use axum_htmx::SwapOption;
 use derive_more::derive::Display;
+use leptos::tachys::html::attribute::{custom::custom_attribute, Attribute};
 
 // region:	  --- HtmxId
 
@@ -123,6 +125,248 @@ impl HtmxInput {
 }
 
 // endregion: --- HtmxInput
+
+// region:	  --- HtmxSwapOob
+
+/// How a component with an id is swapped in when it comes along with another response.
+///
+/// Components that can be updated out of band take it as a prop and put
+/// [`HtmxSwapOob::attribute`] on their root element, the one carrying their id. That element then
+/// replaces its old self, instead of being nested inside it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HtmxSwapOob {
+    /// Swapped in as the target of the response, if at all
+    #[default]
+    None,
+    /// Replaces the element with the same id, wherever it is on the page
+    OuterHtml,
+}
+
+impl HtmxSwapOob {
+    /// Value of the `hx-swap-oob` attribute
+    pub fn attribute(self) -> Option<&'static str> {
+        match self {
+            HtmxSwapOob::None => None,
+            HtmxSwapOob::OuterHtml => Some("outerHTML"),
+        }
+    }
+}
+
+// endregion: --- HtmxSwapOob
+
+// region:	  --- HtmxRoute
+
+/// A route of the app, shared by its router and the components requesting it
+///
+/// Plan routes are requested relative to the plan page, so renaming one in its `routes()` breaks
+/// the build of every component still pointing at it instead of leaving a dead button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HtmxRoute {
+    path: &'static str,
+    relative: bool,
+}
+
+impl HtmxRoute {
+    /// Route below the plan page, `path` as given to its router
+    pub const fn plan(path: &'static str) -> Self {
+        Self {
+            path,
+            relative: true,
+        }
+    }
+
+    /// Route from the root of the app
+    pub const fn root(path: &'static str) -> Self {
+        Self {
+            path,
+            relative: false,
+        }
+    }
+
+    /// Path for the axum router
+    pub fn path(self) -> &'static str {
+        self.path
+    }
+}
+
+impl std::fmt::Display for HtmxRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.relative {
+            write!(f, "{}", self.path.trim_start_matches('/'))
+        } else {
+            write!(f, "{}", self.path)
+        }
+    }
+}
+
+// endregion: --- HtmxRoute
+
+// region:	  --- HtmxVerb
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmxVerb {
+    Get,
+    Post,
+    Delete,
+}
+
+impl HtmxVerb {
+    fn attribute(self) -> &'static str {
+        match self {
+            HtmxVerb::Get => "hx-get",
+            HtmxVerb::Post => "hx-post",
+            HtmxVerb::Delete => "hx-delete",
+        }
+    }
+}
+
+// endregion: --- HtmxVerb
+
+// region:	  --- HtmxTrigger
+
+/// Event that sends the request, when it isn't the default one of the element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmxTrigger {
+    Click,
+    Change,
+    Submit,
+    Load,
+}
+
+impl std::fmt::Display for HtmxTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let event = match self {
+            HtmxTrigger::Click => "click",
+            HtmxTrigger::Change => "change",
+            HtmxTrigger::Submit => "submit",
+            HtmxTrigger::Load => "load",
+        };
+        write!(f, "{event}")
+    }
+}
+
+// endregion: --- HtmxTrigger
+
+// region:	  --- Htmx
+
+/// Value of the `hx-swap` attribute
+fn swap_value(swap: SwapOption) -> &'static str {
+    match swap {
+        SwapOption::InnerHtml => "innerHTML",
+        SwapOption::OuterHtml => "outerHTML",
+        SwapOption::BeforeBegin => "beforebegin",
+        SwapOption::AfterBegin => "afterbegin",
+        SwapOption::BeforeEnd => "beforeend",
+        SwapOption::AfterEnd => "afterend",
+        SwapOption::Delete => "delete",
+        SwapOption::None => "none",
+    }
+}
+
+/// Builds the htmx attributes of an element, spread onto it with `{..Htmx::post(ROUTE).attrs()}`
+#[derive(Debug, Clone)]
+pub struct Htmx {
+    verb: HtmxVerb,
+    route: HtmxRoute,
+    target: Option<String>,
+    swap: Option<SwapOption>,
+    trigger: Option<HtmxTrigger>,
+    push_url: Option<String>,
+    include: Option<String>,
+    vals: Option<String>,
+    confirm: Option<String>,
+}
+
+impl Htmx {
+    pub fn new(verb: HtmxVerb, route: HtmxRoute) -> Self {
+        Self {
+            verb,
+            route,
+            target: None,
+            swap: None,
+            trigger: None,
+            push_url: None,
+            include: None,
+            vals: None,
+            confirm: None,
+        }
+    }
+
+    pub fn get(route: HtmxRoute) -> Self {
+        Self::new(HtmxVerb::Get, route)
+    }
+
+    pub fn post(route: HtmxRoute) -> Self {
+        Self::new(HtmxVerb::Post, route)
+    }
+
+    pub fn delete(route: HtmxRoute) -> Self {
+        Self::new(HtmxVerb::Delete, route)
+    }
+
+    pub fn target(mut self, target: impl Into<HtmxTarget>) -> Self {
+        self.target = Some(target.into().to_string());
+        self
+    }
+
+    /// Targets the closest ancestor matching the css `selector`
+    pub fn target_closest(mut self, selector: &str) -> Self {
+        self.target = Some(format!("closest {selector}"));
+        self
+    }
+
+    pub fn swap(mut self, swap: SwapOption) -> Self {
+        self.swap = Some(swap);
+        self
+    }
+
+    pub fn trigger(mut self, trigger: HtmxTrigger) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
+    /// Pushes the page url with this query into the history
+    pub fn push_url(mut self, query: impl Into<String>) -> Self {
+        self.push_url = Some(query.into());
+        self
+    }
+
+    pub fn include(mut self, include: impl Into<HtmxInclude>) -> Self {
+        self.include = Some(include.into().to_string());
+        self
+    }
+
+    /// Extra values sent along, a json object
+    pub fn vals(mut self, vals: serde_json::Value) -> Self {
+        self.vals = Some(vals.to_string());
+        self
+    }
+
+    /// Asks before sending the request
+    pub fn confirm(mut self, question: impl Into<String>) -> Self {
+        self.confirm = Some(question.into());
+        self
+    }
+
+    pub fn attrs(self) -> impl Attribute {
+        (
+            custom_attribute(self.verb.attribute(), Some(self.route.to_string())),
+            custom_attribute("hx-target", self.target),
+            custom_attribute("hx-swap", self.swap.map(swap_value)),
+            custom_attribute(
+                "hx-trigger",
+                self.trigger.map(|trigger| trigger.to_string()),
+            ),
+            custom_attribute("hx-push-url", self.push_url),
+            custom_attribute("hx-include", self.include),
+            custom_attribute("hx-vals", self.vals),
+            custom_attribute("hx-confirm", self.confirm),
+        )
+    }
+}
+
+// endregion: --- Htmx
+
 // region:    --- Tests
 #[cfg(test)]
 mod tests {
@@ -169,5 +413,46 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_htmx_route() -> Result<()> {
+        let fx_plan_route = HtmxRoute::plan("/calendar/date");
+        let fx_root_route = HtmxRoute::root("/plan");
+
+        assert_eq!(fx_plan_route.path(), "/calendar/date");
+        assert_eq!(fx_plan_route.to_string(), "calendar/date");
+        assert_eq!(fx_root_route.to_string(), "/plan");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_htmx_attrs() -> Result<()> {
+        let fx_route = HtmxRoute::plan("/calendar/dates");
+
+        let html = leptos::prelude::Owner::new().with(|| {
+            use leptos::prelude::*;
+            view! {
+                <button {..Htmx::delete(fx_route)
+                    .target(HtmxId::new("calendar"))
+                    .swap(SwapOption::OuterHtml)
+                    .trigger(HtmxTrigger::Click)
+                    .vals(serde_json::json!({ "clear": true }))
+                    .attrs()}></button>
+            }
+            .to_html()
+        });
+
+        assert!(html.contains(r#"hx-delete="calendar/dates""#));
+        assert!(html.contains(r##"hx-target="#calendar""##));
+        assert!(html.contains(r#"hx-swap="outerHTML""#));
+        assert!(html.contains(r#"hx-trigger="click""#));
+        assert!(html.contains("hx-vals="));
+        // Unset attributes are left out
+        assert!(!html.contains("hx-push-url"));
+        assert!(!html.contains("hx-confirm"));
+
+        Ok(())
+    }
 }
 // endregion: --- Tests
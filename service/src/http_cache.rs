@@ -0,0 +1,160 @@
+use std::time::SystemTime;
+
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use entity::plans;
+use http::{
+    header::{CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    HeaderMap, HeaderValue, StatusCode,
+};
+use sha2::{Digest, Sha256};
+use time::Date;
+
+/// Cache validators for a response that only changes when its plan changes.
+///
+/// The ETag covers the plan's `mtime`, the viewer's `today` and a `variant` describing everything
+/// else the response depends on (the url, the session, the locale, the theme). The response must
+/// be revalidated on every use, so a reload or a month switch becomes a cheap 304 until someone
+/// changes the plan.
+#[derive(Debug, Clone)]
+pub struct PlanVersion {
+    etag: String,
+    last_modified: SystemTime,
+}
+
+impl PlanVersion {
+    pub fn new(plan: &plans::Model, today: Date, variant: &[&str]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(plan.public_id.to_string());
+        hasher.update(plan.mtime.unix_timestamp_nanos().to_be_bytes());
+        // A new day moves the `today` marker, even when the plan didn't change
+        hasher.update(today.to_string());
+        for part in variant {
+            hasher.update([0]);
+            hasher.update(part);
+        }
+        let etag = format!(r#"W/"{}""#, hex::encode(&hasher.finalize()[..16]));
+
+        // Not the exact start of the day in the viewer's zone, but it grows every day all the same
+        let last_modified = SystemTime::from(plan.mtime.max(today.midnight().assume_utc()));
+
+        Self {
+            etag,
+            last_modified,
+        }
+    }
+
+    /// Whether the client's cached copy is still up to date
+    pub fn is_fresh(&self, request_headers: &HeaderMap) -> bool {
+        // `If-None-Match` takes precedence over `If-Modified-Since`
+        if let Some(if_none_match) = request_headers.get(IF_NONE_MATCH) {
+            return if_none_match.to_str().is_ok_and(|tags| {
+                tags.split(',')
+                    .any(|tag| tag.trim() == "*" || weak_eq(tag.trim(), &self.etag))
+            });
+        }
+
+        request_headers
+            .get(IF_MODIFIED_SINCE)
+            .and_then(|since| since.to_str().ok())
+            .and_then(|since| httpdate::parse_http_date(since).ok())
+            .is_some_and(|since| unix_secs(self.last_modified) <= unix_secs(since))
+    }
+
+    pub fn not_modified(self) -> Response {
+        (StatusCode::NOT_MODIFIED, self, ()).into_response()
+    }
+}
+
+impl IntoResponseParts for PlanVersion {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let headers = res.headers_mut();
+
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            headers.insert(ETAG, etag);
+        }
+        if let Ok(last_modified) =
+            HeaderValue::from_str(&httpdate::fmt_http_date(self.last_modified))
+        {
+            headers.insert(LAST_MODIFIED, last_modified);
+        }
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+
+        Ok(res)
+    }
+}
+
+/// HTTP dates only have second precision
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Weak comparison, both `W/"x"` and `"x"` match the same entity
+fn weak_eq(a: &str, b: &str) -> bool {
+    a.trim_start_matches("W/") == b.trim_start_matches("W/")
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use time::OffsetDateTime;
+
+    use crate::test_support::fx_plan;
+
+    fn plan(mtime: OffsetDateTime) -> plans::Model {
+        let mut plan = fx_plan("plan").unwrap();
+        plan.mtime = mtime;
+        plan
+    }
+
+    #[test]
+    fn test_plan_version_etag() -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let today = now.date();
+        let version = PlanVersion::new(&plan(now), today, &["/plan/plan/"]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_str(&version.etag)?);
+        assert!(version.is_fresh(&headers));
+
+        // A change to the plan or another url invalidates the ETag
+        let changed =
+            PlanVersion::new(&plan(now + time::Duration::SECOND), today, &["/plan/plan/"]);
+        assert!(!changed.is_fresh(&headers));
+        let other_url = PlanVersion::new(&plan(now), today, &["/plan/plan/calendar"]);
+        assert!(!other_url.is_fresh(&headers));
+        let next_day = PlanVersion::new(&plan(now), today.next_day().unwrap(), &["/plan/plan/"]);
+        assert!(!next_day.is_fresh(&headers));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_version_if_modified_since() -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let version = PlanVersion::new(&plan(now), now.date(), &[]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            IF_MODIFIED_SINCE,
+            HeaderValue::from_str(&httpdate::fmt_http_date(SystemTime::from(now)))?,
+        );
+        assert!(version.is_fresh(&headers));
+
+        let earlier = now - time::Duration::DAY;
+        headers.insert(
+            IF_MODIFIED_SINCE,
+            HeaderValue::from_str(&httpdate::fmt_http_date(SystemTime::from(earlier)))?,
+        );
+        assert!(!version.is_fresh(&headers));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,159 @@
+//! German
+
+use time::{Month, Weekday};
+
+use super::Text;
+
+pub(super) fn text(text: Text) -> &'static str {
+    match text {
+        Text::AppName => "Pick The Day",
+        Text::PageTitle => "Pick The Day!",
+        Text::HomeTagline => "Plane ein Treffen!",
+        Text::PlanNamePlaceholder => "z. B. Tennis",
+        Text::Create => "Erstellen",
+        Text::NotFoundTitle => "Nicht gefunden",
+        Text::NotFoundMessage => "Seite nicht gefunden",
+        Text::BackToHome => "Zurück zur Startseite",
+        Text::UsernamePlaceholder => "Dein Name",
+        Text::Language => "Sprache",
+        Text::CalendarSettings => "Kalendereinstellungen",
+        Text::WeekStartsOn => "Woche beginnt am",
+        Text::LanguageDefault => "Standard der Sprache",
+        Text::ShowWeekNumbers => "Kalenderwochen anzeigen",
+        Text::Save => "Speichern",
+        Text::WeekNumberShort => "KW",
+        Text::TimeZone => "Zeitzone",
+        Text::ViewMonth => "Monat",
+        Text::ViewTwoMonths => "2 Monate",
+        Text::ViewThreeMonths => "3 Monate",
+        Text::ViewList => "Liste",
+        Text::NoDatesPicked => "Noch keine Termine gewählt",
+        Text::Available => "Verfügbar",
+        Text::Unavailable => "Nicht verfügbar",
+        Text::HeatmapFew => "Wenige",
+        Text::HeatmapEveryone => "Alle",
+        Text::MissingRequired => "Fehlende Pflichtteilnehmer",
+        Text::Required => "Pflicht",
+        Text::Optional => "Optional",
+        Text::MakeRequired => "Als Pflicht markieren",
+        Text::MakeOptional => "Als optional markieren",
+        Text::OwnerLink => "Besitzer-Link",
+        Text::OwnerLinkHint => "öffne ihn in einem anderen Browser, um diesen Plan dort zu verwalten, und halte ihn geheim",
+        Text::MinimumAttendance => "Mindestteilnahme",
+        Text::QuorumReached => "Quorum erreicht",
+        Text::MoreNeeded => "Noch benötigt",
+        Text::Quorum => "Quorum",
+        Text::Webhooks => "Webhooks",
+        Text::WebhooksHint => "Beitritte, Änderungen der Verfügbarkeit, erreichte Quoren und das endgültige Datum werden als JSON an diese URLs gesendet, signiert mit HMAC-SHA256 des Geheimnisses",
+        Text::AddWebhook => "Webhook hinzufügen",
+        Text::DeleteWebhook => "Webhook löschen",
+        Text::SigningSecret => "Geheimnis",
+        Text::RecentDeliveries => "Letzte Zustellungen",
+        Text::FinalDate => "Endgültiges Datum",
+        Text::AddToCalendar => "Zum Kalender hinzufügen",
+        Text::Finalize => "Als endgültiges Datum festlegen",
+        Text::FinalDateHint => "Das Datum, das allen mitgeteilt wird, leer öffnet den Plan wieder",
+        Text::Unsubscribed => "Du bekommst keine E-Mails mehr zu diesem Plan.",
+        Text::BackToPlan => "Zurück zum Plan",
+        Text::EmailPlaceholder => "E-Mail für Updates (optional)",
+        Text::Comments => "Kommentare",
+        Text::AddComment => "Kommentieren",
+        Text::CommentPlaceholder => "Zum Beispiel \"Am 12. kann ich nur nachmittags\"",
+        Text::CommentDateHint => "Optional, das Datum, um das es geht",
+        Text::Anonymous => "Anonym",
+        Text::Polls => "Umfragen",
+        Text::AddPoll => "Umfrage hinzufügen",
+        Text::DeletePoll => "Umfrage löschen",
+        Text::PollQuestionPlaceholder => "Etwa \"Wo treffen wir uns?\"",
+        Text::PollOptionsPlaceholder => "Eine Option pro Zeile",
+        Text::Yes => "Ja",
+        Text::Maybe => "Vielleicht",
+        Text::No => "Nein",
+        Text::RepeatNextMonth => "Nächsten Monat wiederholen",
+        Text::ClonePlan => "Plan kopieren",
+        Text::ClonePlanHint => "Behält die Teilnehmer und Einstellungen, aber keine Daten",
+        Text::ImportDates => "Meine Daten aus einem anderen Plan übernehmen",
+        Text::ImportDatesHint => "Dein Code in diesem Plan:",
+        Text::ImportCodePlaceholder => "Dein Code im anderen Plan",
+        Text::Import => "Übernehmen",
+        Text::SelectWeek => "Ganze Woche auswählen",
+        Text::ClearWeek => "Ganze Woche entfernen",
+        Text::SelectWeekdays => "Alle Wochentage",
+        Text::SelectWeekends => "Alle Wochenenden",
+        Text::ClearAllDates => "Alle meine Daten entfernen",
+        Text::ClearAllDatesConfirm => "Alle deine Daten aus diesem Plan entfernen?",
+        Text::DragHint => "Über Daten ziehen, um sie auszuwählen, oder um sie zu entfernen, wenn du auf einem ausgewählten Datum beginnst",
+        Text::Theme => "Farbschema",
+        Text::ThemeSystem => "System",
+        Text::ThemeLight => "Hell",
+        Text::ThemeDark => "Dunkel",
+        Text::ThemeHighContrast => "Hoher Kontrast",
+        Text::GoBack => "Zurück",
+        Text::TooManyRequests => "Zu viele Anfragen, bitte versuche es gleich noch einmal",
+        Text::RequestRejected => "Anfrage abgelehnt, bitte lade die Seite neu",
+        Text::NotPlanOwner => "Nur wer den Plan erstellt hat, kann das tun",
+        Text::ErrorNotFound => "Nicht gefunden",
+        Text::SomethingWentWrong => "Etwas ist schiefgelaufen",
+        Text::InvalidPlan => "Ungültiger Plan",
+        Text::InvalidUser => "Ungültige Teilnahme",
+        Text::InvalidPoll => "Ungültige Umfrage",
+        Text::LimitReached => "Das Limit ist erreicht",
+        Text::PollTooFewOptions => "Es werden mindestens zwei Optionen benötigt",
+        Text::PollTooManyOptions => "Zu viele Optionen, höchstens",
+        Text::EmailHello => "Hallo",
+        Text::EmailConfirmSubject => "Bestätige deine E-Mail-Adresse",
+        Text::EmailJoined => "Du machst mit bei",
+        Text::EmailConfirmPrompt => "Bestätige deine E-Mail-Adresse, um eine Zusammenfassung zu bekommen, wenn andere antworten, und den Termin, sobald er feststeht:",
+        Text::EmailThePlan => "Der Plan",
+        Text::EmailNotYou => "Nicht du? Ignoriere diese E-Mail, oder bestelle alle E-Mails zu diesem Plan ab",
+        Text::EmailNewResponses => "Neue Antworten zu",
+        Text::EmailSeeAllResponses => "Alle Antworten ansehen",
+        Text::EmailStop => "Diese E-Mails abbestellen",
+        Text::EmailHappeningOn => "findet statt am",
+        Text::EmailCalendarAttached => "Mit der angehängten Datei kommt der Termin in deinen Kalender.",
+        Text::DigestJoined => "macht mit",
+        Text::DigestJoinedAndPicked => "macht mit und wählte",
+        Text::DigestPicked => "wählte",
+    }
+}
+
+pub(super) fn month_name(month: Month) -> &'static str {
+    match month {
+        Month::January => "Januar",
+        Month::February => "Februar",
+        Month::March => "März",
+        Month::April => "April",
+        Month::May => "Mai",
+        Month::June => "Juni",
+        Month::July => "Juli",
+        Month::August => "August",
+        Month::September => "September",
+        Month::October => "Oktober",
+        Month::November => "November",
+        Month::December => "Dezember",
+    }
+}
+
+pub(super) fn weekday_short(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "MO",
+        Weekday::Tuesday => "DI",
+        Weekday::Wednesday => "MI",
+        Weekday::Thursday => "DO",
+        Weekday::Friday => "FR",
+        Weekday::Saturday => "SA",
+        Weekday::Sunday => "SO",
+    }
+}
+
+pub(super) fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "Montag",
+        Weekday::Tuesday => "Dienstag",
+        Weekday::Wednesday => "Mittwoch",
+        Weekday::Thursday => "Donnerstag",
+        Weekday::Friday => "Freitag",
+        Weekday::Saturday => "Samstag",
+        Weekday::Sunday => "Sonntag",
+    }
+}
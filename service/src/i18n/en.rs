@@ -0,0 +1,161 @@
+//! English, the reference catalog
+
+use time::{Month, Weekday};
+
+use super::Text;
+
+pub(super) fn text(text: Text) -> &'static str {
+    match text {
+        Text::AppName => "Pick The Day",
+        Text::PageTitle => "Pick The Day!",
+        Text::HomeTagline => "Create a meetup!",
+        Text::PlanNamePlaceholder => "e.g. Tennis",
+        Text::Create => "Create",
+        Text::NotFoundTitle => "Not Found",
+        Text::NotFoundMessage => "Page not found",
+        Text::BackToHome => "Back to Home",
+        Text::UsernamePlaceholder => "Your name",
+        Text::Language => "Language",
+        Text::CalendarSettings => "Calendar settings",
+        Text::WeekStartsOn => "Week starts on",
+        Text::LanguageDefault => "Language default",
+        Text::ShowWeekNumbers => "Show week numbers",
+        Text::Save => "Save",
+        Text::WeekNumberShort => "WK",
+        Text::TimeZone => "Time zone",
+        Text::ViewMonth => "Month",
+        Text::ViewTwoMonths => "2 months",
+        Text::ViewThreeMonths => "3 months",
+        Text::ViewList => "List",
+        Text::NoDatesPicked => "No dates picked yet",
+        Text::Available => "Available",
+        Text::Unavailable => "Unavailable",
+        Text::HeatmapFew => "Few",
+        Text::HeatmapEveryone => "Everyone",
+        Text::MissingRequired => "Missing required",
+        Text::Required => "Required",
+        Text::Optional => "Optional",
+        Text::MakeRequired => "Make required",
+        Text::MakeOptional => "Make optional",
+        Text::OwnerLink => "Owner link",
+        Text::OwnerLinkHint => {
+            "open it in another browser to manage this plan there, keep it private"
+        }
+        Text::MinimumAttendance => "Minimum attendance",
+        Text::QuorumReached => "Quorum reached",
+        Text::MoreNeeded => "More needed",
+        Text::Quorum => "Quorum",
+        Text::Webhooks => "Webhooks",
+        Text::WebhooksHint => "Joins, availability changes, reached quorums and the final date are posted to these urls as JSON, signed with HMAC-SHA256 of the secret",
+        Text::AddWebhook => "Add webhook",
+        Text::DeleteWebhook => "Delete webhook",
+        Text::SigningSecret => "Secret",
+        Text::RecentDeliveries => "Recent deliveries",
+        Text::FinalDate => "Final date",
+        Text::AddToCalendar => "Add to calendar",
+        Text::Finalize => "Pick as final date",
+        Text::FinalDateHint => "The date everyone gets announced, empty reopens the plan",
+        Text::Unsubscribed => "You won't get any more emails about this plan.",
+        Text::BackToPlan => "Back to the plan",
+        Text::EmailPlaceholder => "Email for updates (optional)",
+        Text::Comments => "Comments",
+        Text::AddComment => "Comment",
+        Text::CommentPlaceholder => "Like \"I can only do afternoons on the 12th\"",
+        Text::CommentDateHint => "Optional, the date the comment is about",
+        Text::Anonymous => "Anonymous",
+        Text::Polls => "Polls",
+        Text::AddPoll => "Add a poll",
+        Text::DeletePoll => "Delete poll",
+        Text::PollQuestionPlaceholder => "Like \"Where do we meet?\"",
+        Text::PollOptionsPlaceholder => "One option per line",
+        Text::Yes => "Yes",
+        Text::Maybe => "Maybe",
+        Text::No => "No",
+        Text::RepeatNextMonth => "Repeat next month",
+        Text::ClonePlan => "Copy plan",
+        Text::ClonePlanHint => "Keeps the participants and settings, but none of the dates",
+        Text::ImportDates => "Copy my dates from another plan",
+        Text::ImportDatesHint => "Your code in this plan:",
+        Text::ImportCodePlaceholder => "Your code in the other plan",
+        Text::Import => "Copy",
+        Text::SelectWeek => "Pick the whole week",
+        Text::ClearWeek => "Clear the whole week",
+        Text::SelectWeekdays => "All weekdays",
+        Text::SelectWeekends => "All weekends",
+        Text::ClearAllDates => "Clear all my dates",
+        Text::ClearAllDatesConfirm => "Remove all your dates from this plan?",
+        Text::DragHint => "Drag across dates to pick them, or to clear them when starting on a picked date",
+        Text::Theme => "Theme",
+        Text::ThemeSystem => "System",
+        Text::ThemeLight => "Light",
+        Text::ThemeDark => "Dark",
+        Text::ThemeHighContrast => "High contrast",
+        Text::GoBack => "Go back",
+        Text::TooManyRequests => "Too many requests, please try again in a moment",
+        Text::RequestRejected => "Request rejected, please reload the page",
+        Text::NotPlanOwner => "Only the owner of the plan can do this",
+        Text::ErrorNotFound => "Not found",
+        Text::SomethingWentWrong => "Something went wrong",
+        Text::InvalidPlan => "Invalid plan",
+        Text::InvalidUser => "Invalid participant",
+        Text::InvalidPoll => "Invalid poll",
+        Text::LimitReached => "The limit is reached",
+        Text::PollTooFewOptions => "At least two options are needed",
+        Text::PollTooManyOptions => "Too many options, at most",
+        Text::EmailHello => "Hi",
+        Text::EmailConfirmSubject => "Confirm your email address",
+        Text::EmailJoined => "You joined",
+        Text::EmailConfirmPrompt => "Confirm your email address to get a digest when others respond, and the final date once it is picked:",
+        Text::EmailThePlan => "The plan",
+        Text::EmailNotYou => "Not you? Ignore this email, or stop all emails about this plan",
+        Text::EmailNewResponses => "New responses to",
+        Text::EmailSeeAllResponses => "See all responses",
+        Text::EmailStop => "Stop these emails",
+        Text::EmailHappeningOn => "is happening on",
+        Text::EmailCalendarAttached => "The attached file adds it to your calendar.",
+        Text::DigestJoined => "joined",
+        Text::DigestJoinedAndPicked => "joined and picked",
+        Text::DigestPicked => "picked",
+    }
+}
+
+pub(super) fn month_name(month: Month) -> &'static str {
+    match month {
+        Month::January => "January",
+        Month::February => "February",
+        Month::March => "March",
+        Month::April => "April",
+        Month::May => "May",
+        Month::June => "June",
+        Month::July => "July",
+        Month::August => "August",
+        Month::September => "September",
+        Month::October => "October",
+        Month::November => "November",
+        Month::December => "December",
+    }
+}
+
+pub(super) fn weekday_short(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "MON",
+        Weekday::Tuesday => "TUE",
+        Weekday::Wednesday => "WED",
+        Weekday::Thursday => "THU",
+        Weekday::Friday => "FRI",
+        Weekday::Saturday => "SAT",
+        Weekday::Sunday => "SUN",
+    }
+}
+
+pub(super) fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "Monday",
+        Weekday::Tuesday => "Tuesday",
+        Weekday::Wednesday => "Wednesday",
+        Weekday::Thursday => "Thursday",
+        Weekday::Friday => "Friday",
+        Weekday::Saturday => "Saturday",
+        Weekday::Sunday => "Sunday",
+    }
+}
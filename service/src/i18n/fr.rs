@@ -0,0 +1,161 @@
+//! French
+
+use time::{Month, Weekday};
+
+use super::Text;
+
+pub(super) fn text(text: Text) -> &'static str {
+    match text {
+        Text::AppName => "Pick The Day",
+        Text::PageTitle => "Pick The Day !",
+        Text::HomeTagline => "Organisez une rencontre !",
+        Text::PlanNamePlaceholder => "p. ex. Tennis",
+        Text::Create => "Créer",
+        Text::NotFoundTitle => "Introuvable",
+        Text::NotFoundMessage => "Page introuvable",
+        Text::BackToHome => "Retour à l'accueil",
+        Text::UsernamePlaceholder => "Votre nom",
+        Text::Language => "Langue",
+        Text::CalendarSettings => "Paramètres du calendrier",
+        Text::WeekStartsOn => "La semaine commence le",
+        Text::LanguageDefault => "Selon la langue",
+        Text::ShowWeekNumbers => "Afficher les numéros de semaine",
+        Text::Save => "Enregistrer",
+        Text::WeekNumberShort => "SEM",
+        Text::TimeZone => "Fuseau horaire",
+        Text::ViewMonth => "Mois",
+        Text::ViewTwoMonths => "2 mois",
+        Text::ViewThreeMonths => "3 mois",
+        Text::ViewList => "Liste",
+        Text::NoDatesPicked => "Aucune date choisie pour l'instant",
+        Text::Available => "Disponible",
+        Text::Unavailable => "Indisponible",
+        Text::HeatmapFew => "Peu",
+        Text::HeatmapEveryone => "Tout le monde",
+        Text::MissingRequired => "Obligatoires absents",
+        Text::Required => "Obligatoire",
+        Text::Optional => "Facultatif",
+        Text::MakeRequired => "Rendre obligatoire",
+        Text::MakeOptional => "Rendre facultatif",
+        Text::OwnerLink => "Lien propriétaire",
+        Text::OwnerLinkHint => {
+            "ouvrez-le dans un autre navigateur pour y gérer ce plan, gardez-le privé"
+        }
+        Text::MinimumAttendance => "Participation minimale",
+        Text::QuorumReached => "Quorum atteint",
+        Text::MoreNeeded => "Encore nécessaires",
+        Text::Quorum => "Quorum",
+        Text::Webhooks => "Webhooks",
+        Text::WebhooksHint => "Les arrivées, les changements de disponibilité, les quorums atteints et la date retenue sont envoyés en JSON à ces urls, signés en HMAC-SHA256 avec le secret",
+        Text::AddWebhook => "Ajouter un webhook",
+        Text::DeleteWebhook => "Supprimer le webhook",
+        Text::SigningSecret => "Secret",
+        Text::RecentDeliveries => "Livraisons récentes",
+        Text::FinalDate => "Date finale",
+        Text::AddToCalendar => "Ajouter au calendrier",
+        Text::Finalize => "Choisir comme date finale",
+        Text::FinalDateHint => "La date annoncée à tous, vide rouvre le plan",
+        Text::Unsubscribed => "Vous ne recevrez plus d'e-mails sur ce plan.",
+        Text::BackToPlan => "Retour au plan",
+        Text::EmailPlaceholder => "E-mail pour les mises à jour (facultatif)",
+        Text::Comments => "Commentaires",
+        Text::AddComment => "Commenter",
+        Text::CommentPlaceholder => "Par exemple \"Le 12, je ne peux que l'après-midi\"",
+        Text::CommentDateHint => "Facultatif, la date concernée",
+        Text::Anonymous => "Anonyme",
+        Text::Polls => "Sondages",
+        Text::AddPoll => "Ajouter un sondage",
+        Text::DeletePoll => "Supprimer le sondage",
+        Text::PollQuestionPlaceholder => "Par exemple \"Où se retrouve-t-on ?\"",
+        Text::PollOptionsPlaceholder => "Une option par ligne",
+        Text::Yes => "Oui",
+        Text::Maybe => "Peut-être",
+        Text::No => "Non",
+        Text::RepeatNextMonth => "Répéter le mois prochain",
+        Text::ClonePlan => "Copier le plan",
+        Text::ClonePlanHint => "Garde les participants et les réglages, mais aucune date",
+        Text::ImportDates => "Copier mes dates d'un autre plan",
+        Text::ImportDatesHint => "Votre code dans ce plan :",
+        Text::ImportCodePlaceholder => "Votre code dans l'autre plan",
+        Text::Import => "Copier",
+        Text::SelectWeek => "Choisir toute la semaine",
+        Text::ClearWeek => "Retirer toute la semaine",
+        Text::SelectWeekdays => "Tous les jours de semaine",
+        Text::SelectWeekends => "Tous les week-ends",
+        Text::ClearAllDates => "Retirer toutes mes dates",
+        Text::ClearAllDatesConfirm => "Retirer toutes vos dates de ce plan ?",
+        Text::DragHint => "Faites glisser sur des dates pour les choisir, ou pour les retirer en partant d'une date choisie",
+        Text::Theme => "Thème",
+        Text::ThemeSystem => "Système",
+        Text::ThemeLight => "Clair",
+        Text::ThemeDark => "Sombre",
+        Text::ThemeHighContrast => "Contraste élevé",
+        Text::GoBack => "Retour",
+        Text::TooManyRequests => "Trop de requêtes, veuillez réessayer dans un instant",
+        Text::RequestRejected => "Requête refusée, veuillez recharger la page",
+        Text::NotPlanOwner => "Seul le propriétaire du plan peut faire cela",
+        Text::ErrorNotFound => "Introuvable",
+        Text::SomethingWentWrong => "Une erreur s'est produite",
+        Text::InvalidPlan => "Plan invalide",
+        Text::InvalidUser => "Participant invalide",
+        Text::InvalidPoll => "Sondage invalide",
+        Text::LimitReached => "La limite est atteinte",
+        Text::PollTooFewOptions => "Il faut au moins deux options",
+        Text::PollTooManyOptions => "Trop d'options, au maximum",
+        Text::EmailHello => "Bonjour",
+        Text::EmailConfirmSubject => "Confirmez votre adresse e-mail",
+        Text::EmailJoined => "Vous participez à",
+        Text::EmailConfirmPrompt => "Confirmez votre adresse e-mail pour recevoir un résumé quand les autres répondent, et la date finale dès qu'elle est choisie :",
+        Text::EmailThePlan => "Le plan",
+        Text::EmailNotYou => "Ce n'est pas vous ? Ignorez cet e-mail, ou arrêtez tous les e-mails de ce plan",
+        Text::EmailNewResponses => "Nouvelles réponses à",
+        Text::EmailSeeAllResponses => "Voir toutes les réponses",
+        Text::EmailStop => "Ne plus recevoir ces e-mails",
+        Text::EmailHappeningOn => "a lieu le",
+        Text::EmailCalendarAttached => "Le fichier joint l'ajoute à votre agenda.",
+        Text::DigestJoined => "participe",
+        Text::DigestJoinedAndPicked => "participe et a choisi",
+        Text::DigestPicked => "a choisi",
+    }
+}
+
+pub(super) fn month_name(month: Month) -> &'static str {
+    match month {
+        Month::January => "janvier",
+        Month::February => "février",
+        Month::March => "mars",
+        Month::April => "avril",
+        Month::May => "mai",
+        Month::June => "juin",
+        Month::July => "juillet",
+        Month::August => "août",
+        Month::September => "septembre",
+        Month::October => "octobre",
+        Month::November => "novembre",
+        Month::December => "décembre",
+    }
+}
+
+pub(super) fn weekday_short(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "LUN",
+        Weekday::Tuesday => "MAR",
+        Weekday::Wednesday => "MER",
+        Weekday::Thursday => "JEU",
+        Weekday::Friday => "VEN",
+        Weekday::Saturday => "SAM",
+        Weekday::Sunday => "DIM",
+    }
+}
+
+pub(super) fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "lundi",
+        Weekday::Tuesday => "mardi",
+        Weekday::Wednesday => "mercredi",
+        Weekday::Thursday => "jeudi",
+        Weekday::Friday => "vendredi",
+        Weekday::Saturday => "samedi",
+        Weekday::Sunday => "dimanche",
+    }
+}
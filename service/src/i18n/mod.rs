@@ -0,0 +1,294 @@
+//! Locales and the translation catalogs of all text shown in the UI.
+//!
+//! Every catalog matches exhaustively on [`Text`], so a missing translation is a compile error.
+
+use std::str::FromStr;
+
+use derive_more::derive::Display;
+use leptos::prelude::*;
+use time::{Month, Weekday};
+
+use crate::request_context::RequestContext;
+
+mod de;
+mod en;
+mod fr;
+mod nl;
+
+// region:	  --- Locale
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display)]
+pub enum Locale {
+    #[default]
+    #[display("en")]
+    En,
+    #[display("de")]
+    De,
+    #[display("fr")]
+    Fr,
+    #[display("nl")]
+    Nl,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Fr, Locale::Nl];
+
+    /// Name of the language in the language itself, for the language picker
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::De => "Deutsch",
+            Locale::Fr => "Français",
+            Locale::Nl => "Nederlands",
+        }
+    }
+
+    /// First day of the week for plans that don't set one.
+    ///
+    /// Locales don't carry a region, so English follows the US convention.
+    pub fn week_start(self) -> Weekday {
+        match self {
+            Locale::En => Weekday::Sunday,
+            Locale::De | Locale::Fr | Locale::Nl => Weekday::Monday,
+        }
+    }
+
+    pub fn text(self, text: Text) -> &'static str {
+        match self {
+            Locale::En => en::text(text),
+            Locale::De => de::text(text),
+            Locale::Fr => fr::text(text),
+            Locale::Nl => nl::text(text),
+        }
+    }
+
+    pub fn month_name(self, month: Month) -> &'static str {
+        match self {
+            Locale::En => en::month_name(month),
+            Locale::De => de::month_name(month),
+            Locale::Fr => fr::month_name(month),
+            Locale::Nl => nl::month_name(month),
+        }
+    }
+
+    pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+        match self {
+            Locale::En => en::weekday_name(weekday),
+            Locale::De => de::weekday_name(weekday),
+            Locale::Fr => fr::weekday_name(weekday),
+            Locale::Nl => nl::weekday_name(weekday),
+        }
+    }
+
+    /// Abbreviated weekday for the calendar header
+    pub fn weekday_short(self, weekday: Weekday) -> &'static str {
+        match self {
+            Locale::En => en::weekday_short(weekday),
+            Locale::De => de::weekday_short(weekday),
+            Locale::Fr => fr::weekday_short(weekday),
+            Locale::Nl => nl::weekday_short(weekday),
+        }
+    }
+
+    /// Picks the supported locale the client prefers most from an `Accept-Language` header
+    pub fn from_accept_language(accept_language: &str) -> Option<Locale> {
+        let mut preferences: Vec<(f32, Locale)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let locale = parts.next()?.trim().parse().ok()?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse().ok())?;
+
+                (quality > 0.0).then_some((quality, locale))
+            })
+            .collect();
+
+        // Stable sort, so equal weights keep the client's order
+        preferences.sort_by(|a, b| b.0.total_cmp(&a.0));
+        preferences.first().map(|(_, locale)| *locale)
+    }
+}
+
+/// Parses a language tag, ignoring the region, so `de-AT` is `De`
+impl FromStr for Locale {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or_default();
+
+        Locale::ALL
+            .into_iter()
+            .find(|locale| language.eq_ignore_ascii_case(&locale.to_string()))
+            .ok_or(())
+    }
+}
+
+// endregion: --- Locale
+
+// region:	  --- Text
+
+/// Keys of the UI text, translated by the catalog of each locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    AppName,
+    PageTitle,
+    HomeTagline,
+    PlanNamePlaceholder,
+    Create,
+    NotFoundTitle,
+    NotFoundMessage,
+    BackToHome,
+    UsernamePlaceholder,
+    Language,
+    CalendarSettings,
+    WeekStartsOn,
+    LanguageDefault,
+    ShowWeekNumbers,
+    Save,
+    WeekNumberShort,
+    TimeZone,
+    ViewMonth,
+    ViewTwoMonths,
+    ViewThreeMonths,
+    ViewList,
+    NoDatesPicked,
+    Available,
+    Unavailable,
+    HeatmapFew,
+    HeatmapEveryone,
+    MissingRequired,
+    Required,
+    Optional,
+    MakeRequired,
+    MakeOptional,
+    OwnerLink,
+    OwnerLinkHint,
+    MinimumAttendance,
+    QuorumReached,
+    MoreNeeded,
+    Quorum,
+    Webhooks,
+    WebhooksHint,
+    AddWebhook,
+    DeleteWebhook,
+    SigningSecret,
+    RecentDeliveries,
+    FinalDate,
+    AddToCalendar,
+    Finalize,
+    FinalDateHint,
+    Unsubscribed,
+    BackToPlan,
+    EmailPlaceholder,
+    Comments,
+    AddComment,
+    CommentPlaceholder,
+    CommentDateHint,
+    Anonymous,
+    Polls,
+    AddPoll,
+    DeletePoll,
+    PollQuestionPlaceholder,
+    PollOptionsPlaceholder,
+    Yes,
+    Maybe,
+    No,
+    RepeatNextMonth,
+    ClonePlan,
+    ClonePlanHint,
+    ImportDates,
+    ImportDatesHint,
+    ImportCodePlaceholder,
+    Import,
+    SelectWeek,
+    ClearWeek,
+    SelectWeekdays,
+    SelectWeekends,
+    ClearAllDates,
+    ClearAllDatesConfirm,
+    DragHint,
+    Theme,
+    ThemeSystem,
+    ThemeLight,
+    ThemeDark,
+    ThemeHighContrast,
+    GoBack,
+    TooManyRequests,
+    RequestRejected,
+    NotPlanOwner,
+    ErrorNotFound,
+    SomethingWentWrong,
+    InvalidPlan,
+    InvalidUser,
+    InvalidPoll,
+    LimitReached,
+    PollTooFewOptions,
+    PollTooManyOptions,
+    EmailHello,
+    EmailConfirmSubject,
+    EmailJoined,
+    EmailConfirmPrompt,
+    EmailThePlan,
+    EmailNotYou,
+    EmailNewResponses,
+    EmailSeeAllResponses,
+    EmailStop,
+    EmailHappeningOn,
+    EmailCalendarAttached,
+    DigestJoined,
+    DigestJoinedAndPicked,
+    DigestPicked,
+}
+
+// endregion: --- Text
+
+// region:	  --- Component helpers
+
+/// Locale of the request being rendered, see [`RequestContext::render`]
+pub fn use_locale() -> Locale {
+    use_context::<RequestContext>()
+        .map(|context| context.locale)
+        .unwrap_or_default()
+}
+
+/// Translates `text` into the locale of the request being rendered
+pub fn t(text: Text) -> &'static str {
+    use_locale().text(text)
+}
+
+// endregion: --- Component helpers
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_locale_from_accept_language() -> Result<()> {
+        let fx_cases = [
+            ("de-DE,de;q=0.9,en;q=0.8", Some(Locale::De)),
+            ("en-US,en;q=0.9", Some(Locale::En)),
+            // Unsupported languages are skipped
+            ("ja,nl;q=0.5", Some(Locale::Nl)),
+            // The quality decides, not the order
+            ("en;q=0.4, fr;q=0.8", Some(Locale::Fr)),
+            ("fr;q=0, *", None),
+            ("", None),
+        ];
+
+        for (accept_language, expected) in fx_cases {
+            assert_eq!(
+                Locale::from_accept_language(accept_language),
+                expected,
+                "{accept_language}"
+            );
+        }
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,161 @@
+//! Dutch
+
+use time::{Month, Weekday};
+
+use super::Text;
+
+pub(super) fn text(text: Text) -> &'static str {
+    match text {
+        Text::AppName => "Pick The Day",
+        Text::PageTitle => "Pick The Day!",
+        Text::HomeTagline => "Plan een afspraak!",
+        Text::PlanNamePlaceholder => "bijv. Tennis",
+        Text::Create => "Maken",
+        Text::NotFoundTitle => "Niet gevonden",
+        Text::NotFoundMessage => "Pagina niet gevonden",
+        Text::BackToHome => "Terug naar de startpagina",
+        Text::UsernamePlaceholder => "Je naam",
+        Text::Language => "Taal",
+        Text::CalendarSettings => "Kalenderinstellingen",
+        Text::WeekStartsOn => "Week begint op",
+        Text::LanguageDefault => "Standaard van de taal",
+        Text::ShowWeekNumbers => "Weeknummers tonen",
+        Text::Save => "Opslaan",
+        Text::WeekNumberShort => "WK",
+        Text::TimeZone => "Tijdzone",
+        Text::ViewMonth => "Maand",
+        Text::ViewTwoMonths => "2 maanden",
+        Text::ViewThreeMonths => "3 maanden",
+        Text::ViewList => "Lijst",
+        Text::NoDatesPicked => "Nog geen datums gekozen",
+        Text::Available => "Beschikbaar",
+        Text::Unavailable => "Niet beschikbaar",
+        Text::HeatmapFew => "Weinig",
+        Text::HeatmapEveryone => "Iedereen",
+        Text::MissingRequired => "Ontbrekende verplichte deelnemers",
+        Text::Required => "Verplicht",
+        Text::Optional => "Optioneel",
+        Text::MakeRequired => "Verplicht maken",
+        Text::MakeOptional => "Optioneel maken",
+        Text::OwnerLink => "Eigenaarslink",
+        Text::OwnerLinkHint => {
+            "open hem in een andere browser om dit plan daar te beheren, houd hem privé"
+        }
+        Text::MinimumAttendance => "Minimale opkomst",
+        Text::QuorumReached => "Quorum bereikt",
+        Text::MoreNeeded => "Nog nodig",
+        Text::Quorum => "Quorum",
+        Text::Webhooks => "Webhooks",
+        Text::WebhooksHint => "Aanmeldingen, gewijzigde beschikbaarheid, bereikte quorums en de definitieve datum worden als JSON naar deze urls gestuurd, ondertekend met HMAC-SHA256 van het geheim",
+        Text::AddWebhook => "Webhook toevoegen",
+        Text::DeleteWebhook => "Webhook verwijderen",
+        Text::SigningSecret => "Geheim",
+        Text::RecentDeliveries => "Recente afleveringen",
+        Text::FinalDate => "Definitieve datum",
+        Text::AddToCalendar => "Toevoegen aan agenda",
+        Text::Finalize => "Kies als definitieve datum",
+        Text::FinalDateHint => "De datum die iedereen te horen krijgt, leeg heropent het plan",
+        Text::Unsubscribed => "Je krijgt geen e-mails meer over dit plan.",
+        Text::BackToPlan => "Terug naar het plan",
+        Text::EmailPlaceholder => "E-mail voor updates (optioneel)",
+        Text::Comments => "Opmerkingen",
+        Text::AddComment => "Reageren",
+        Text::CommentPlaceholder => "Bijvoorbeeld \"Op de 12e kan ik alleen 's middags\"",
+        Text::CommentDateHint => "Optioneel, de datum waar het over gaat",
+        Text::Anonymous => "Anoniem",
+        Text::Polls => "Peilingen",
+        Text::AddPoll => "Peiling toevoegen",
+        Text::DeletePoll => "Peiling verwijderen",
+        Text::PollQuestionPlaceholder => "Zoals \"Waar spreken we af?\"",
+        Text::PollOptionsPlaceholder => "Eén optie per regel",
+        Text::Yes => "Ja",
+        Text::Maybe => "Misschien",
+        Text::No => "Nee",
+        Text::RepeatNextMonth => "Volgende maand herhalen",
+        Text::ClonePlan => "Plan kopiëren",
+        Text::ClonePlanHint => "Behoudt de deelnemers en instellingen, maar geen van de datums",
+        Text::ImportDates => "Mijn datums uit een ander plan overnemen",
+        Text::ImportDatesHint => "Jouw code in dit plan:",
+        Text::ImportCodePlaceholder => "Jouw code in het andere plan",
+        Text::Import => "Overnemen",
+        Text::SelectWeek => "Hele week kiezen",
+        Text::ClearWeek => "Hele week wissen",
+        Text::SelectWeekdays => "Alle weekdagen",
+        Text::SelectWeekends => "Alle weekenden",
+        Text::ClearAllDates => "Al mijn datums wissen",
+        Text::ClearAllDatesConfirm => "Al je datums uit dit plan wissen?",
+        Text::DragHint => "Sleep over datums om ze te kiezen, of om ze te wissen als je op een gekozen datum begint",
+        Text::Theme => "Thema",
+        Text::ThemeSystem => "Systeem",
+        Text::ThemeLight => "Licht",
+        Text::ThemeDark => "Donker",
+        Text::ThemeHighContrast => "Hoog contrast",
+        Text::GoBack => "Terug",
+        Text::TooManyRequests => "Te veel verzoeken, probeer het zo opnieuw",
+        Text::RequestRejected => "Verzoek geweigerd, laad de pagina opnieuw",
+        Text::NotPlanOwner => "Alleen de eigenaar van het plan kan dit doen",
+        Text::ErrorNotFound => "Niet gevonden",
+        Text::SomethingWentWrong => "Er ging iets mis",
+        Text::InvalidPlan => "Ongeldig plan",
+        Text::InvalidUser => "Ongeldige deelnemer",
+        Text::InvalidPoll => "Ongeldige peiling",
+        Text::LimitReached => "De limiet is bereikt",
+        Text::PollTooFewOptions => "Er zijn minstens twee opties nodig",
+        Text::PollTooManyOptions => "Te veel opties, maximaal",
+        Text::EmailHello => "Hoi",
+        Text::EmailConfirmSubject => "Bevestig je e-mailadres",
+        Text::EmailJoined => "Je doet mee aan",
+        Text::EmailConfirmPrompt => "Bevestig je e-mailadres om een overzicht te krijgen als anderen reageren, en de definitieve datum zodra die gekozen is:",
+        Text::EmailThePlan => "Het plan",
+        Text::EmailNotYou => "Ben jij dit niet? Negeer deze e-mail, of stop alle e-mails over dit plan",
+        Text::EmailNewResponses => "Nieuwe reacties op",
+        Text::EmailSeeAllResponses => "Bekijk alle reacties",
+        Text::EmailStop => "Stop deze e-mails",
+        Text::EmailHappeningOn => "vindt plaats op",
+        Text::EmailCalendarAttached => "Met het bijgevoegde bestand zet je het in je agenda.",
+        Text::DigestJoined => "doet mee",
+        Text::DigestJoinedAndPicked => "doet mee en koos",
+        Text::DigestPicked => "koos",
+    }
+}
+
+pub(super) fn month_name(month: Month) -> &'static str {
+    match month {
+        Month::January => "januari",
+        Month::February => "februari",
+        Month::March => "maart",
+        Month::April => "april",
+        Month::May => "mei",
+        Month::June => "juni",
+        Month::July => "juli",
+        Month::August => "augustus",
+        Month::September => "september",
+        Month::October => "oktober",
+        Month::November => "november",
+        Month::December => "december",
+    }
+}
+
+pub(super) fn weekday_short(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "MA",
+        Weekday::Tuesday => "DI",
+        Weekday::Wednesday => "WO",
+        Weekday::Thursday => "DO",
+        Weekday::Friday => "VR",
+        Weekday::Saturday => "ZA",
+        Weekday::Sunday => "ZO",
+    }
+}
+
+pub(super) fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "maandag",
+        Weekday::Tuesday => "dinsdag",
+        Weekday::Wednesday => "woensdag",
+        Weekday::Thursday => "donderdag",
+        Weekday::Friday => "vrijdag",
+        Weekday::Saturday => "zaterdag",
+        Weekday::Sunday => "zondag",
+    }
+}
@@ -1,29 +1,55 @@
-use config::web_config;
+use config::{web_config, LogFormat};
 use fileserv::file_and_error_handler;
 
 pub mod app;
 pub mod config;
 pub mod error;
+pub mod events;
 pub mod fileserv;
 pub mod htmx_helpers;
+pub mod http_cache;
+pub mod i18n;
+pub mod middleware;
+pub mod notifier;
 pub mod plan_page;
+pub mod request_context;
+pub mod theme;
 pub mod util_components;
+pub mod webhooks;
+
+#[cfg(test)]
+mod test_support;
 
 #[tokio::main]
 async fn main() {
     use axum::Router;
+    use middleware::{
+        mw_csrf::mw_csrf,
+        mw_error_response::mw_error_response,
+        mw_locale::mw_locale,
+        mw_rate_limit::{mw_rate_limit, RateLimiter},
+        mw_request_trace::{make_request_span, REQUEST_ID_HEADER},
+        mw_security_headers::mw_security_headers,
+        mw_theme::mw_theme,
+        mw_time_zone::mw_time_zone,
+    };
+    use std::net::SocketAddr;
+    use tower::ServiceBuilder;
+    use tower_http::{
+        request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+        trace::{DefaultOnResponse, TraceLayer},
+    };
     use tracing::info;
 
-    // Setup tracing subscriber
-    tracing_subscriber::fmt()
-        .without_time()
-        .with_target(false)
-        .with_max_level(tracing::Level::DEBUG)
-        .init();
-
     // Get config
     let config = &web_config();
 
+    // Setup tracing subscriber
+    init_tracing(config.LOG_FORMAT);
+
+    // Fingerprint the assets, which all have to be there
+    fileserv::init_assets();
+
     // Get the DB
     let mm = entity::db::ModelManager::new(config.DATABASE_URL.clone())
         .await
@@ -32,16 +58,74 @@ async fn main() {
     // Run migrations
     mm.run_migrations().await.expect("Migrations failed!");
 
+    // Event subscribers
+    tokio::spawn(events::log_events());
+    tokio::spawn(webhooks::deliver_events(mm.clone()));
+    if let Some(notifier) =
+        notifier::Notifier::from_config(config).expect("Email config is invalid")
+    {
+        let notifier = std::sync::Arc::new(notifier);
+        tokio::spawn(notifier::notify_events(notifier.clone(), mm.clone()));
+        tokio::spawn(notifier::send_digests(
+            notifier,
+            std::time::Duration::from_secs(config.DIGEST_INTERVAL_MINUTES * 60),
+            mm.clone(),
+        ));
+    }
+
     // build our application with a route
     let app = Router::new()
         .merge(app::routes(mm.clone()))
         .merge(plan_page::routes(mm.clone()))
         .fallback(file_and_error_handler)
-        .layer(tower_http::compression::CompressionLayer::new().zstd(true));
+        .layer(axum::middleware::from_fn(mw_csrf))
+        .layer(axum::middleware::from_fn_with_state(
+            RateLimiter::general(),
+            mw_rate_limit,
+        ))
+        // Inside the layers that set up the page, outside the ones that reject requests
+        .layer(axum::middleware::from_fn(mw_error_response))
+        .layer(axum::middleware::from_fn(mw_locale))
+        .layer(axum::middleware::from_fn(mw_theme))
+        .layer(axum::middleware::from_fn(mw_time_zone))
+        .layer(axum::middleware::from_fn(mw_security_headers))
+        .layer(tower_http::compression::CompressionLayer::new().zstd(true))
+        .layer(
+            ServiceBuilder::new()
+                .layer(SetRequestIdLayer::new(
+                    REQUEST_ID_HEADER.clone(),
+                    MakeRequestUuid,
+                ))
+                .layer(
+                    TraceLayer::new_for_http()
+                        .make_span_with(make_request_span)
+                        .on_response(DefaultOnResponse::new().level(tracing::Level::INFO)),
+                )
+                .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER.clone())),
+        );
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
     info!("{:<12} - {:?}\n", "LISTENING", listener.local_addr());
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
+
+fn init_tracing(log_format: LogFormat) {
+    let subscriber = tracing_subscriber::fmt()
+        .with_target(false)
+        .with_max_level(tracing::Level::DEBUG);
+
+    match log_format {
+        LogFormat::Text => subscriber.without_time().init(),
+        // Keep the timestamp and the full span list, so entity spans still carry the request span
+        LogFormat::Json => subscriber
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            .init(),
+    }
 }
@@ -0,0 +1,9 @@
+pub mod mw_csrf;
+pub mod mw_error_response;
+pub mod mw_locale;
+pub mod mw_rate_limit;
+pub mod mw_request_trace;
+pub mod mw_security_headers;
+pub mod mw_theme;
+pub mod mw_time_zone;
+pub mod preference;
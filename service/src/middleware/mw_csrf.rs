@@ -0,0 +1,256 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+use derive_more::derive::Display;
+use http::{
+    header::{CONTENT_TYPE, HOST, ORIGIN, REFERER},
+    HeaderMap, HeaderName, Method, Uri,
+};
+
+use crate::error::{Error, Result};
+
+/// Header that htmx sends the token in, set on `<body>` through `hx-headers`
+pub static CSRF_HEADER: HeaderName = HeaderName::from_static("x-csrf-token");
+/// Field that plain HTML forms send the token in, they can't set headers
+pub const CSRF_FIELD: &str = "csrf_token";
+/// Same as axum's default body limit
+const FORM_LIMIT: usize = 2 * 1024 * 1024;
+const CSRF_COOKIE: &str = "csrf_token";
+const CSRF_TOKEN_LEN: usize = 32;
+
+// region:	  --- CSRF token
+
+/// Per-session token, stored in a cookie and echoed by htmx in the `X-CSRF-Token` header, or
+/// by plain forms in the `csrf_token` field
+#[derive(Debug, Clone, Display, PartialEq, Eq)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    pub fn generate() -> Self {
+        use rand::distributions::Alphanumeric;
+        use rand::{thread_rng, Rng};
+
+        let token = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(CSRF_TOKEN_LEN)
+            .map(char::from)
+            .collect();
+
+        Self(token)
+    }
+
+    /// The token of the session, when it already has one
+    pub fn from_cookies(jar: &CookieJar) -> Option<Self> {
+        jar.get(CSRF_COOKIE)
+            .and_then(|cookie| Self::parse(cookie.value()))
+    }
+
+    fn parse(token: &str) -> Option<Self> {
+        (token.len() == CSRF_TOKEN_LEN && token.chars().all(|c| c.is_ascii_alphanumeric()))
+            .then(|| Self(token.to_string()))
+    }
+
+    /// Compares the tokens without short-circuiting on the first difference
+    fn matches(&self, other: &str) -> bool {
+        let (a, b) = (self.0.as_bytes(), other.as_bytes());
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+// endregion: --- CSRF token
+
+// region:	  --- CSRF middleware
+
+/// Makes sure every session has a CSRF token and verifies it on every state-changing request.
+///
+/// The token is put in the request extensions so pages can render it into `hx-headers`.
+pub async fn mw_csrf(jar: CookieJar, mut request: Request<Body>, next: Next) -> Result<Response> {
+    let existing_token = CsrfToken::from_cookies(&jar);
+
+    if !is_safe_method(request.method()) {
+        verify_same_origin(request.headers())?;
+
+        let token = existing_token
+            .as_ref()
+            .ok_or(Error::CsrfRejected("missing CSRF cookie"))?;
+        let header_token = request
+            .headers()
+            .get(&CSRF_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let request_token = match header_token {
+            Some(header_token) => header_token,
+            None => {
+                let (form_request, form_token) = form_token(request).await?;
+                request = form_request;
+                form_token.ok_or(Error::CsrfRejected("missing CSRF token"))?
+            }
+        };
+
+        if !token.matches(&request_token) {
+            return Err(Error::CsrfRejected("invalid CSRF token"));
+        }
+    }
+
+    let token = existing_token.clone().unwrap_or_else(CsrfToken::generate);
+    request.extensions_mut().insert(token.clone());
+
+    let response = next.run(request).await;
+
+    // -- Start the session on the first page that renders the token
+    if existing_token.is_none() && is_html(&response) {
+        let cookie = Cookie::build((CSRF_COOKIE, token.to_string()))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Strict);
+        return Ok((jar.add(cookie), response).into_response());
+    }
+
+    Ok(response)
+}
+
+/// Reads the token field of a form post, putting the body back for the handler
+async fn form_token(request: Request<Body>) -> Result<(Request<Body>, Option<String>)> {
+    let is_form = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/x-www-form-urlencoded"));
+    if !is_form {
+        return Ok((request, None));
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, FORM_LIMIT)
+        .await
+        .map_err(|_| Error::CsrfRejected("unreadable form"))?;
+    let token = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+        .ok()
+        .and_then(|fields| {
+            fields
+                .into_iter()
+                .find_map(|(name, value)| (name == CSRF_FIELD).then_some(value))
+        });
+
+    Ok((Request::from_parts(parts, Body::from(bytes)), token))
+}
+
+fn is_html(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/html"))
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Checks that the `Origin`, or the `Referer` when there is no `Origin`, matches the `Host`
+fn verify_same_origin(headers: &HeaderMap) -> Result<()> {
+    let host = headers
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .ok_or(Error::CsrfRejected("missing Host header"))?;
+
+    let source = headers
+        .get(ORIGIN)
+        .or_else(|| headers.get(REFERER))
+        .and_then(|source| source.to_str().ok())
+        .and_then(|source| source.parse::<Uri>().ok())
+        .ok_or(Error::CsrfRejected("missing Origin or Referer header"))?;
+
+    match source.authority() {
+        Some(authority) if authority.as_str().eq_ignore_ascii_case(host) => Ok(()),
+        _ => Err(Error::CsrfRejected("cross-origin request")),
+    }
+}
+
+// endregion: --- CSRF middleware
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use axum::{middleware::from_fn, routing::post, Router};
+    use http::StatusCode;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "abcdefghijklmnopqrstuvwxyz012345";
+
+    fn app() -> Router {
+        Router::new()
+            .route("/", post(|| async { StatusCode::CREATED }))
+            .layer(from_fn(mw_csrf))
+    }
+
+    fn post_request(origin: &str) -> http::request::Builder {
+        Request::post("/")
+            .header(HOST, "picktheday.test")
+            .header(ORIGIN, origin)
+    }
+
+    #[tokio::test]
+    async fn test_csrf_valid_token_ok() -> Result<()> {
+        let request = post_request("https://picktheday.test")
+            .header("cookie", format!("{CSRF_COOKIE}={TOKEN}"))
+            .header(&CSRF_HEADER, TOKEN)
+            .body(Body::empty())?;
+
+        let response = app().oneshot(request).await?;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_csrf_missing_token_err() -> Result<()> {
+        let request = post_request("https://picktheday.test")
+            .header("cookie", format!("{CSRF_COOKIE}={TOKEN}"))
+            .body(Body::empty())?;
+
+        let response = app().oneshot(request).await?;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_csrf_form_field_ok() -> Result<()> {
+        let request = post_request("https://picktheday.test")
+            .header("cookie", format!("{CSRF_COOKIE}={TOKEN}"))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!(
+                "plan_name=Book+club&{CSRF_FIELD}={TOKEN}"
+            )))?;
+
+        let response = app().oneshot(request).await?;
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_csrf_cross_origin_err() -> Result<()> {
+        let request = post_request("https://evil.test")
+            .header("cookie", format!("{CSRF_COOKIE}={TOKEN}"))
+            .header(&CSRF_HEADER, TOKEN)
+            .body(Body::empty())?;
+
+        let response = app().oneshot(request).await?;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::Request,
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use axum_htmx::HxRequest;
+use http::{
+    header::{REFERER, RETRY_AFTER},
+    Uri,
+};
+use leptos::prelude::*;
+
+use crate::{
+    app::{ErrorPage, Page},
+    error::Error,
+    i18n::{t, Locale, Text},
+    middleware::{mw_csrf::CsrfToken, mw_security_headers::CspNonce},
+    request_context::RequestContext,
+    theme::Theme,
+};
+
+/// Renders errors in the locale of the request, as a whole page for requests without htmx.
+///
+/// `Error::into_response` renders a fragment for htmx to swap into the page, which would be all
+/// that is left of the page after a plain form post or link.
+pub async fn mw_error_response(
+    HxRequest(htmx): HxRequest,
+    jar: CookieJar,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let context = error_context(&jar, &request);
+    let back = back_path(&request);
+
+    let response = next.run(request).await;
+
+    let Some(error) = response.extensions().get::<Arc<Error>>().cloned() else {
+        return response;
+    };
+    let Some(context) = context else {
+        return response;
+    };
+    if htmx {
+        let mut fragment = error.fragment_response(context.locale);
+        fragment.extensions_mut().insert(error);
+        return fragment;
+    }
+
+    let (status, message) = error.client_status_and_message(context.locale);
+    let content = context.render(|| {
+        view! {
+            <Page title=t(Text::PageTitle).to_string()>
+                <ErrorPage message=message back=back/>
+            </Page>
+        }
+    });
+
+    let mut page = (status, Html(content)).into_response();
+    if let Some(retry_after) = response.headers().get(RETRY_AFTER) {
+        page.headers_mut().insert(RETRY_AFTER, retry_after.clone());
+    }
+    page.extensions_mut().insert(error);
+
+    page
+}
+
+/// The context of the outer middlewares, the CSRF token only exists once `mw_csrf` ran
+fn error_context(jar: &CookieJar, request: &Request<Body>) -> Option<RequestContext> {
+    let extensions = request.extensions();
+
+    Some(RequestContext {
+        csrf_token: CsrfToken::from_cookies(jar).unwrap_or_else(CsrfToken::generate),
+        csp_nonce: extensions.get::<CspNonce>()?.clone(),
+        locale: extensions.get::<Locale>().copied().unwrap_or_default(),
+        theme: extensions.get::<Theme>().copied().unwrap_or_default(),
+        time_zone: extensions.get().cloned(),
+        query: request.uri().query().map(str::to_string),
+    })
+}
+
+/// The page the request came from, only its path so the link stays on this site
+fn back_path(request: &Request<Body>) -> String {
+    request
+        .headers()
+        .get(REFERER)
+        .and_then(|referer| referer.to_str().ok())
+        .and_then(|referer| referer.parse::<Uri>().ok())
+        .and_then(|referer| referer.path_and_query().map(|path| path.to_string()))
+        .filter(|path| path.starts_with('/') && !path.starts_with("//"))
+        .unwrap_or_else(|| "/".to_string())
+}
@@ -0,0 +1,25 @@
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use axum_extra::extract::CookieJar;
+use http::header::ACCEPT_LANGUAGE;
+
+use crate::{i18n::Locale, middleware::preference::Preference};
+
+/// Switched with `?lang=de`, then the cookie, then `Accept-Language`
+const LOCALE: Preference<Locale> = Preference {
+    query_param: "lang",
+    cookie: "lang",
+    parse: |value| value.parse().ok(),
+    fallback: |request| {
+        let header_locale = request
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Locale::from_accept_language);
+        Some(header_locale.unwrap_or_default())
+    },
+};
+
+/// Picks the locale of the request and puts it in the request extensions
+pub async fn mw_locale(jar: CookieJar, request: Request<Body>, next: Next) -> Response {
+    LOCALE.run(jar, request, next).await
+}
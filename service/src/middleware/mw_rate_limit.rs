@@ -0,0 +1,259 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use dashmap::DashMap;
+use http::{header::FORWARDED, HeaderMap};
+
+use crate::{
+    config::web_config,
+    error::{Error, Result},
+};
+
+/// Above this many tracked IPs, buckets that have fully refilled are dropped
+const MAX_TRACKED_IPS: usize = 10_000;
+
+// region:	  --- Rate limit middleware
+
+/// Rejects requests from IPs that have exhausted their token bucket with a 429
+pub async fn mw_rate_limit(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response> {
+    let ip = client_ip(request.headers(), addr.ip(), web_config().TRUSTED_PROXIES);
+    limiter
+        .try_acquire(ip)
+        .map_err(|retry_after| Error::RateLimited {
+            retry_after_secs: retry_after.as_secs().max(1),
+        })?;
+
+    Ok(next.run(request).await)
+}
+
+/// The IP of the client, behind `trusted_proxies` reverse proxies.
+///
+/// Each proxy appends the address it got the request from, so the client is that many entries
+/// from the end. Entries before it come from the client and can't be trusted.
+fn client_ip(headers: &HeaderMap, peer: IpAddr, trusted_proxies: usize) -> IpAddr {
+    if trusted_proxies == 0 {
+        return peer;
+    }
+
+    let forwarded_for = if headers.contains_key(FORWARDED) {
+        forwarded_header_chain(headers)
+    } else {
+        header_values(headers, "x-forwarded-for")
+    };
+
+    // A shorter chain starts at a trusted proxy, its first entry is the client
+    let client = forwarded_for.len().saturating_sub(trusted_proxies);
+    forwarded_for
+        .get(client)
+        .and_then(|address| parse_node(address))
+        .unwrap_or(peer)
+}
+
+/// The `for=` addresses of the `Forwarded` header (RFC 7239)
+fn forwarded_header_chain(headers: &HeaderMap) -> Vec<String> {
+    header_values(headers, FORWARDED.as_str())
+        .into_iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| value.trim().trim_matches('"').to_string())
+            })
+        })
+        .collect()
+}
+
+/// The comma separated entries of all the headers with `name`
+fn header_values(headers: &HeaderMap, name: &str) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|entry| entry.trim().to_string())
+        .collect()
+}
+
+/// An address like `192.0.2.60`, `192.0.2.60:4711` or `[2001:db8::17]:4711`
+fn parse_node(address: &str) -> Option<IpAddr> {
+    address
+        .parse::<IpAddr>()
+        .or_else(|_| address.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .or_else(|_| {
+            address
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse()
+        })
+        .ok()
+}
+
+// endregion: --- Rate limit middleware
+
+// region:	  --- Rate limiter
+
+/// Per-IP token bucket rate limiter, cheap to clone and share between routes
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    buckets: Arc<DashMap<IpAddr, TokenBucket>>,
+    capacity: f64,
+    refill_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(burst: u32, per_minute: u32) -> Self {
+        Self {
+            buckets: Arc::new(DashMap::new()),
+            capacity: burst.max(1) as f64,
+            refill_per_sec: per_minute as f64 / 60.0,
+        }
+    }
+
+    /// Budget for all routes
+    pub fn general() -> Self {
+        let config = web_config();
+        Self::new(config.RATE_LIMIT_BURST, config.RATE_LIMIT_PER_MINUTE)
+    }
+
+    /// Stricter budget for routes that create plans or participants
+    pub fn creation() -> Self {
+        let config = web_config();
+        Self::new(
+            config.CREATE_RATE_LIMIT_BURST,
+            config.CREATE_RATE_LIMIT_PER_MINUTE,
+        )
+    }
+
+    /// Takes a token for `ip`, or returns how long to wait until one is available
+    pub fn try_acquire(&self, ip: IpAddr) -> std::result::Result<(), Duration> {
+        self.try_acquire_at(ip, Instant::now())
+    }
+
+    fn try_acquire_at(&self, ip: IpAddr, now: Instant) -> std::result::Result<(), Duration> {
+        if self.buckets.len() > MAX_TRACKED_IPS {
+            self.prune(now);
+        }
+
+        let mut bucket = self.buckets.entry(ip).or_insert(TokenBucket {
+            tokens: self.capacity,
+            last_refill: now,
+        });
+
+        // -- Refill based on the time passed since the last request
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if self.refill_per_sec > 0.0 {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / self.refill_per_sec))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+
+    /// Drops the buckets that would be full by now, they are the same as a new bucket
+    fn prune(&self, now: Instant) {
+        self.buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.last_refill);
+            bucket.tokens + elapsed.as_secs_f64() * self.refill_per_sec < self.capacity
+        });
+    }
+}
+
+// endregion: --- Rate limiter
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_rate_limiter_burst_then_refill() -> Result<()> {
+        let limiter = RateLimiter::new(2, 60);
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_at(ip, start).is_ok());
+        assert!(limiter.try_acquire_at(ip, start).is_ok());
+        let retry_after = limiter.try_acquire_at(ip, start).unwrap_err();
+        assert_eq!(retry_after.as_secs(), 1);
+
+        // One token per second is refilled
+        assert!(limiter
+            .try_acquire_at(ip, start + Duration::from_secs(1))
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_client_ip_behind_proxy() -> Result<()> {
+        let peer = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let mut headers = HeaderMap::new();
+        // The client made up the first entry, the proxy appended the real one
+        headers.insert("x-forwarded-for", "1.1.1.1, 203.0.113.7".parse()?);
+
+        assert_eq!(client_ip(&headers, peer, 0), peer);
+        assert_eq!(
+            client_ip(&headers, peer, 1),
+            "203.0.113.7".parse::<IpAddr>()?
+        );
+        assert_eq!(client_ip(&headers, peer, 2), "1.1.1.1".parse::<IpAddr>()?);
+        assert_eq!(client_ip(&HeaderMap::new(), peer, 1), peer);
+
+        headers.insert(
+            FORWARDED,
+            r#"for=1.1.1.1, for="[2001:db8:cafe::17]:4711";proto=https"#.parse()?,
+        );
+        assert_eq!(
+            client_ip(&headers, peer, 1),
+            "2001:db8:cafe::17".parse::<IpAddr>()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limiter_per_ip() -> Result<()> {
+        let limiter = RateLimiter::new(1, 1);
+        let ip1 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let ip2 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(ip1, now).is_ok());
+        assert!(limiter.try_acquire_at(ip1, now).is_err());
+        assert!(limiter.try_acquire_at(ip2, now).is_ok());
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,34 @@
+use axum::{body::Body, extract::MatchedPath};
+use http::{HeaderName, Request};
+use tower_http::request_id::RequestId;
+use tracing::{info_span, Span};
+
+/// Header carrying the request ID, both on the incoming request and the response
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Creates the span that wraps a request from routing until the response is sent,
+/// so everything logged by the handlers and the entity calls carries its request ID and route.
+///
+/// Expects `SetRequestIdLayer` to run first, otherwise the `request_id` is empty.
+pub fn make_request_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .unwrap_or_default();
+
+    // The fallback (static files, not found) has no matched route
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or("fallback");
+
+    info_span!(
+        "request",
+        request_id,
+        method = %request.method(),
+        route,
+        uri = %request.uri(),
+    )
+}
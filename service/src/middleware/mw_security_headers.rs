@@ -0,0 +1,93 @@
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use derive_more::derive::Display;
+use http::{
+    header::{
+        CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS,
+    },
+    HeaderValue, StatusCode,
+};
+
+const CSP_NONCE_LEN: usize = 24;
+
+/// Per-request nonce that allows the inline scripts rendered by `Page` to run
+#[derive(Debug, Clone, Display)]
+pub struct CspNonce(String);
+
+impl CspNonce {
+    pub fn generate() -> Self {
+        use rand::distributions::Alphanumeric;
+        use rand::{thread_rng, Rng};
+
+        let nonce = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(CSP_NONCE_LEN)
+            .map(char::from)
+            .collect();
+
+        Self(nonce)
+    }
+}
+
+/// Adds the security headers to every response, including a Content-Security-Policy
+/// with a fresh nonce that is put in the request extensions for the page to render.
+pub async fn mw_security_headers(mut request: Request<Body>, next: Next) -> Response {
+    let nonce = CspNonce::generate();
+    request.extensions_mut().insert(nonce.clone());
+
+    let mut response = next.run(request).await;
+    let status = response.status();
+    let headers = response.headers_mut();
+
+    // A 304 reuses the cached page, which only works with the nonce of the cached policy
+    if status != StatusCode::NOT_MODIFIED {
+        if let Ok(csp) = HeaderValue::from_str(&content_security_policy(&nonce)) {
+            headers.insert(CONTENT_SECURITY_POLICY, csp);
+        }
+    }
+    headers.insert(
+        STRICT_TRANSPORT_SECURITY,
+        HeaderValue::from_static("max-age=63072000; includeSubDomains"),
+    );
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    // Plan urls are the only access control, so never leak them to other sites
+    headers.insert(REFERRER_POLICY, HeaderValue::from_static("same-origin"));
+
+    response
+}
+
+fn content_security_policy(nonce: &CspNonce) -> String {
+    [
+        "default-src 'self'".to_string(),
+        // Alpine's CSP build only looks up the components registered in public/calendar.js
+        format!("script-src 'self' 'nonce-{nonce}'"),
+        "style-src 'self'".to_string(),
+        "img-src 'self' data:".to_string(),
+        "object-src 'none'".to_string(),
+        "base-uri 'self'".to_string(),
+        "form-action 'self'".to_string(),
+        "frame-ancestors 'none'".to_string(),
+    ]
+    .join("; ")
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_content_security_policy_no_eval() -> Result<()> {
+        let fx_nonce = CspNonce::generate();
+
+        let csp = content_security_policy(&fx_nonce);
+
+        assert!(csp.contains(&format!("script-src 'self' 'nonce-{fx_nonce}'")));
+        assert!(!csp.contains("unsafe-eval"));
+        assert!(!csp.contains("unsafe-inline"));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
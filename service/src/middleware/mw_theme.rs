@@ -0,0 +1,17 @@
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use axum_extra::extract::CookieJar;
+
+use crate::{middleware::preference::Preference, theme::Theme};
+
+/// Switched with `?theme=light`, then the cookie, then the system theme
+const THEME: Preference<Theme> = Preference {
+    query_param: "theme",
+    cookie: "theme",
+    parse: |value| value.parse().ok(),
+    fallback: |_| Some(Theme::default()),
+};
+
+/// Picks the theme of the request and puts it in the request extensions
+pub async fn mw_theme(jar: CookieJar, request: Request<Body>, next: Next) -> Response {
+    THEME.run(jar, request, next).await
+}
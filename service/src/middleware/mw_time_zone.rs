@@ -0,0 +1,21 @@
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+use axum_extra::extract::CookieJar;
+use entity::types::TimeZoneName;
+
+use crate::middleware::preference::Preference;
+
+/// Also set by the detection script in `Page`
+pub const TIME_ZONE_COOKIE: &str = "tz";
+
+/// Set with `?tz=Europe/Amsterdam`, otherwise the cookie the browser set from its own zone
+const TIME_ZONE: Preference<TimeZoneName> = Preference {
+    query_param: "tz",
+    cookie: TIME_ZONE_COOKIE,
+    parse: |value| TimeZoneName::new(value).ok(),
+    fallback: |_| None,
+};
+
+/// Puts the viewer's time zone in the request extensions, when it is known
+pub async fn mw_time_zone(jar: CookieJar, request: Request<Body>, next: Next) -> Response {
+    TIME_ZONE.run(jar, request, next).await
+}
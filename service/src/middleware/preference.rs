@@ -0,0 +1,60 @@
+use std::{collections::HashMap, fmt::Display};
+
+use axum::{
+    body::Body,
+    extract::{Query, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+use time::Duration;
+
+/// A setting of the visitor that a query parameter switches and a cookie remembers.
+///
+/// The value goes in the request extensions: the query parameter wins, then the cookie, then
+/// `fallback`, which can leave it unknown.
+pub struct Preference<T> {
+    pub query_param: &'static str,
+    pub cookie: &'static str,
+    pub parse: fn(&str) -> Option<T>,
+    pub fallback: fn(&Request<Body>) -> Option<T>,
+}
+
+impl<T> Preference<T>
+where
+    T: Clone + PartialEq + Display + Send + Sync + 'static,
+{
+    pub async fn run(&self, jar: CookieJar, mut request: Request<Body>, next: Next) -> Response {
+        let query_value = Query::<HashMap<String, String>>::try_from_uri(request.uri())
+            .ok()
+            .and_then(|Query(params)| (self.parse)(params.get(self.query_param)?));
+        let cookie_value = jar
+            .get(self.cookie)
+            .and_then(|cookie| (self.parse)(cookie.value()));
+
+        let value = query_value
+            .clone()
+            .or(cookie_value.clone())
+            .or_else(|| (self.fallback)(&request));
+        if let Some(value) = value {
+            request.extensions_mut().insert(value);
+        }
+
+        let response = next.run(request).await;
+
+        // -- Remember an explicit choice for the next pages
+        match query_value {
+            Some(value) if Some(&value) != cookie_value.as_ref() => {
+                let cookie = Cookie::build((self.cookie, value.to_string()))
+                    .path("/")
+                    .max_age(Duration::days(365))
+                    .same_site(SameSite::Lax);
+                (jar.add(cookie), response).into_response()
+            }
+            _ => response,
+        }
+    }
+}
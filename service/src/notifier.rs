@@ -0,0 +1,618 @@
+//! Email notifications over SMTP, only to users that confirmed their address.
+//!
+//! Joining with an address sends a confirmation link, responses of the others are collected in
+//! a periodic digest and the final date is announced with a calendar file. Every email carries
+//! a one click unsubscribe link, also in the `List-Unsubscribe` header.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use entity::{db::ModelManager, plans, users};
+use lettre::{
+    message::{
+        header::{ContentType, Header, HeaderName, HeaderValue},
+        Attachment, Mailbox, MultiPart, SinglePart,
+    },
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use time::{Date, OffsetDateTime};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+use crate::{
+    config::WebConfig,
+    error::{Error, Result},
+    events::{self, PlanEvent},
+    i18n::{Locale, Text},
+    plan_page::{final_date::final_date_ics, UserWithDates},
+};
+
+pub struct Notifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    public_url: String,
+}
+
+impl Notifier {
+    /// `None` without an SMTP url, email notifications are off then
+    pub fn from_config(config: &WebConfig) -> Result<Option<Self>> {
+        config
+            .SMTP_URL
+            .as_deref()
+            .map(|smtp_url| Self::new(smtp_url, &config.MAIL_FROM, &config.PUBLIC_URL))
+            .transpose()
+    }
+
+    pub fn new(smtp_url: &str, from: &str, public_url: &str) -> Result<Self> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(smtp_url)
+            .map_err(|_| Error::ConfigWrongFormat("SERVICE_SMTP_URL"))?
+            .build();
+        let from = from
+            .parse()
+            .map_err(|_| Error::ConfigWrongFormat("SERVICE_MAIL_FROM"))?;
+
+        Ok(Self {
+            transport,
+            from,
+            public_url: public_url.to_string(),
+        })
+    }
+
+    pub async fn send(&self, message: Message) -> Result<()> {
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+
+    // region:	  --- Emails
+
+    /// Asks to confirm the address, sent even though it is not confirmed yet
+    pub fn confirmation_email(
+        &self,
+        plan: &plans::Model,
+        user: &users::Model,
+    ) -> Result<Option<Message>> {
+        let Some(email) = &user.email else {
+            return Ok(None);
+        };
+        let locale = email_locale(user);
+        let text = format!(
+            "{hello} {name},\n\n\
+             {joined} \"{plan_name}\". {confirm}\n\n\
+             {confirm_url}\n\n\
+             {the_plan}: {plan_url}\n\n\
+             {not_you}: {unsubscribe_url}\n",
+            hello = locale.text(Text::EmailHello),
+            name = user.name,
+            joined = locale.text(Text::EmailJoined),
+            plan_name = plan.name,
+            confirm = locale.text(Text::EmailConfirmPrompt),
+            confirm_url = self.email_link(plan, user, "confirm"),
+            the_plan = locale.text(Text::EmailThePlan),
+            plan_url = self.plan_url(plan),
+            not_you = locale.text(Text::EmailNotYou),
+            unsubscribe_url = self.email_link(plan, user, "unsubscribe"),
+        );
+
+        let message = self
+            .message(plan, user, email.as_str())?
+            .subject(format!(
+                "{}: \"{}\"",
+                locale.text(Text::EmailConfirmSubject),
+                plan.name
+            ))
+            .header(ContentType::TEXT_PLAIN)
+            .body(text)?;
+        Ok(Some(message))
+    }
+
+    /// The responses since the last digest, `lines` from [`digest_lines`] in the user's locale
+    pub fn digest_email(
+        &self,
+        plan: &plans::Model,
+        user: &users::Model,
+        lines: &[String],
+    ) -> Result<Option<Message>> {
+        let Some(email) = user.notification_email() else {
+            return Ok(None);
+        };
+        let locale = email_locale(user);
+        let new_responses = locale.text(Text::EmailNewResponses);
+        let text = format!(
+            "{hello} {name},\n\n\
+             {new_responses} \"{plan_name}\":\n\n\
+             {lines}\n\n\
+             {see_all}: {plan_url}\n\n\
+             {stop}: {unsubscribe_url}\n",
+            hello = locale.text(Text::EmailHello),
+            name = user.name,
+            plan_name = plan.name,
+            lines = lines
+                .iter()
+                .map(|line| format!("- {line}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            see_all = locale.text(Text::EmailSeeAllResponses),
+            plan_url = self.plan_url(plan),
+            stop = locale.text(Text::EmailStop),
+            unsubscribe_url = self.email_link(plan, user, "unsubscribe"),
+        );
+
+        let message = self
+            .message(plan, user, email.as_str())?
+            .subject(format!("{new_responses} \"{}\"", plan.name))
+            .header(ContentType::TEXT_PLAIN)
+            .body(text)?;
+        Ok(Some(message))
+    }
+
+    /// The final date, with the calendar file attached
+    pub fn announcement_email(
+        &self,
+        plan: &plans::Model,
+        user: &users::Model,
+        date: Date,
+    ) -> Result<Option<Message>> {
+        let Some(email) = user.notification_email() else {
+            return Ok(None);
+        };
+        let locale = email_locale(user);
+        let happening_on = locale.text(Text::EmailHappeningOn);
+        let text = format!(
+            "{hello} {name},\n\n\
+             \"{plan_name}\" {happening_on} {date}. {attached}\n\n\
+             {plan_url}\n\n\
+             {stop}: {unsubscribe_url}\n",
+            hello = locale.text(Text::EmailHello),
+            name = user.name,
+            plan_name = plan.name,
+            date = format_date(date, locale),
+            attached = locale.text(Text::EmailCalendarAttached),
+            plan_url = self.plan_url(plan),
+            stop = locale.text(Text::EmailStop),
+            unsubscribe_url = self.email_link(plan, user, "unsubscribe"),
+        );
+        let calendar = Attachment::new("plan.ics".to_string()).body(
+            final_date_ics(plan, date, &self.public_url),
+            ContentType::parse("text/calendar; charset=utf-8; method=PUBLISH")
+                .expect("Calendar content type is valid"),
+        );
+
+        let message = self
+            .message(plan, user, email.as_str())?
+            .subject(format!(
+                "\"{}\" {happening_on} {}",
+                plan.name,
+                format_date(date, locale)
+            ))
+            .multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(text))
+                    .singlepart(calendar),
+            )?;
+        Ok(Some(message))
+    }
+
+    fn message(
+        &self,
+        plan: &plans::Model,
+        user: &users::Model,
+        email: &str,
+    ) -> Result<lettre::message::MessageBuilder> {
+        Ok(Message::builder()
+            .from(self.from.clone())
+            .to(Mailbox::new(Some(user.name.to_string()), email.parse()?))
+            .header(ListUnsubscribe(self.email_link(plan, user, "unsubscribe"))))
+    }
+
+    fn plan_url(&self, plan: &plans::Model) -> String {
+        format!("{}/plan/{}/", self.public_url, plan.public_id)
+    }
+
+    /// Link to the `/email/{action}` routes, proven by the email token of the user
+    fn email_link(&self, plan: &plans::Model, user: &users::Model, action: &str) -> String {
+        format!(
+            "{}email/{action}?user={}&token={}",
+            self.plan_url(plan),
+            user.public_id,
+            user.email_token
+                .as_ref()
+                .map(|token| token.expose())
+                .unwrap_or_default()
+        )
+    }
+
+    // endregion: --- Emails
+
+    // region:	  --- Workers
+
+    async fn notify_event(&self, event: &PlanEvent, mm: ModelManager) -> Result<()> {
+        match event {
+            PlanEvent::UserJoined {
+                plan_public_id,
+                user_public_id,
+            } => {
+                let user =
+                    users::helpers::user_by_public_id(user_public_id.clone(), mm.clone()).await?;
+                if user.email_confirmed {
+                    return Ok(());
+                }
+                let plan =
+                    plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+                if let Some(message) = self.confirmation_email(&plan, &user)? {
+                    self.send(message).await?;
+                }
+            }
+            PlanEvent::PlanFinalized {
+                plan_public_id,
+                date,
+            } => {
+                let plan =
+                    plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+                for user in users::helpers::notified_users(Some(plan.id), mm.clone()).await? {
+                    let sent = match self.announcement_email(&plan, &user, *date)? {
+                        Some(message) => self.send(message).await,
+                        None => Ok(()),
+                    };
+                    // One bad address doesn't keep the others from hearing about it
+                    if let Err(err) = sent {
+                        warn!(
+                            "{:<12} - announcement to {} - {err}",
+                            "NOTIFIER", user.public_id
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn send_digests(&self, mm: ModelManager) -> Result<()> {
+        let mut recipients_by_plan: HashMap<i32, Vec<users::Model>> = HashMap::new();
+        for user in users::helpers::notified_users(None, mm.clone()).await? {
+            recipients_by_plan
+                .entry(user.plan_id)
+                .or_default()
+                .push(user);
+        }
+
+        for (plan_id, recipients) in recipients_by_plan {
+            // Taken before loading the responses, so later ones make it into the next digest
+            let now = OffsetDateTime::now_utc();
+            let plan = plans::helpers::plan_by_id(plan_id, mm.clone()).await?;
+            let users_with_dates = users::helpers::get_users_with_date_for_plan_public_id(
+                plan.public_id.clone(),
+                mm.clone(),
+            )
+            .await?;
+
+            for recipient in recipients {
+                let since = recipient.digest_sent_at.unwrap_or(recipient.ctime);
+                let lines = digest_lines(
+                    &users_with_dates,
+                    recipient.id,
+                    since,
+                    email_locale(&recipient),
+                );
+                if lines.is_empty() {
+                    continue;
+                }
+                let Some(message) = self.digest_email(&plan, &recipient, &lines)? else {
+                    continue;
+                };
+                match self.send(message).await {
+                    Ok(()) => {
+                        users::helpers::set_digest_sent_at(recipient.id, now, mm.clone()).await?
+                    }
+                    Err(err) => {
+                        warn!(
+                            "{:<12} - digest to {} - {err}",
+                            "NOTIFIER", recipient.public_id
+                        )
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // endregion: --- Workers
+}
+
+/// Sends the emails triggered by events, runs for the lifetime of the server
+pub async fn notify_events(notifier: Arc<Notifier>, mm: ModelManager) {
+    let mut events = events::subscribe();
+
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                // A slow mail server doesn't hold up the next events
+                let notifier = notifier.clone();
+                let mm = mm.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = notifier.notify_event(&event, mm).await {
+                        warn!("{:<12} - {} - {err}", "NOTIFIER", event.name());
+                    }
+                });
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!("{:<12} - missed {missed} events", "NOTIFIER")
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Sends the digests every `interval`, runs for the lifetime of the server
+pub async fn send_digests(notifier: Arc<Notifier>, interval: Duration, mm: ModelManager) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+        debug!("{:<12} - sending digests", "NOTIFIER");
+        if let Err(err) = notifier.send_digests(mm.clone()).await {
+            warn!("{:<12} - digests - {err}", "NOTIFIER");
+        }
+    }
+}
+
+/// What the other users did after `since`: joining and picking dates. Removed dates leave no
+/// trace, so they don't show up.
+pub fn digest_lines(
+    users_with_dates: &[UserWithDates],
+    recipient_id: i32,
+    since: OffsetDateTime,
+    locale: Locale,
+) -> Vec<String> {
+    users_with_dates
+        .iter()
+        .filter(|(user, _)| user.id != recipient_id)
+        .filter_map(|(user, dates)| {
+            let joined = user.ctime > since;
+            let mut picked: Vec<Date> = dates
+                .iter()
+                .filter(|date_model| date_model.ctime > since)
+                .map(|date_model| date_model.date)
+                .collect();
+            picked.sort();
+            let picked = picked
+                .into_iter()
+                .map(|date| format_date(date, locale))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let name = &user.name;
+            match (joined, picked.is_empty()) {
+                (true, true) => Some(format!("{name} {}", locale.text(Text::DigestJoined))),
+                (true, false) => Some(format!(
+                    "{name} {} {picked}",
+                    locale.text(Text::DigestJoinedAndPicked)
+                )),
+                (false, false) => Some(format!(
+                    "{name} {} {picked}",
+                    locale.text(Text::DigestPicked)
+                )),
+                (false, true) => None,
+            }
+        })
+        .collect()
+}
+
+fn format_date(date: Date, locale: Locale) -> String {
+    format!(
+        "{} {} {} {}",
+        locale.weekday_name(date.weekday()),
+        date.day(),
+        locale.month_name(date.month()),
+        date.year()
+    )
+}
+
+/// The locale the address was given in, English for addresses from before
+fn email_locale(user: &users::Model) -> Locale {
+    user.email_locale
+        .as_deref()
+        .and_then(|locale| locale.parse().ok())
+        .unwrap_or_default()
+}
+
+// region:	  --- List-Unsubscribe header
+
+#[derive(Debug, Clone)]
+struct ListUnsubscribe(String);
+
+impl Header for ListUnsubscribe {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe")
+    }
+
+    fn parse(s: &str) -> std::result::Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self(
+            s.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string(),
+        ))
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), format!("<{}>", self.0))
+    }
+}
+
+// endregion: --- List-Unsubscribe header
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use entity::types::{EmailAddress, PublicId, Secret};
+    use std::sync::Mutex;
+    use time::{Month, OffsetDateTime};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    use crate::test_support::{fx_date, fx_plan, fx_user};
+
+    /// A user with a confirmed address, who joined at `ctime`
+    fn fx_recipient(id: i32, name: &str, ctime: OffsetDateTime) -> Result<users::Model> {
+        let mut user = fx_user(id, name)?;
+        user.ctime = ctime;
+        user.email = Some(EmailAddress::new(&format!("{name}@example.com"))?);
+        user.email_token = Some(Secret::default());
+        user.email_confirmed = true;
+
+        Ok(user)
+    }
+
+    /// Takes every email over SMTP and keeps its data
+    async fn fx_smtp_sink() -> Result<(u16, Arc<Mutex<Vec<String>>>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = received.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let sink = sink.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    let mut data: Option<String> = None;
+                    write.write_all(b"220 sink ESMTP\r\n").await?;
+
+                    while let Some(line) = lines.next_line().await? {
+                        if let Some(body) = data.as_mut() {
+                            if line == "." {
+                                sink.lock().unwrap().extend(data.take());
+                                write.write_all(b"250 OK\r\n").await?;
+                            } else {
+                                body.push_str(&line);
+                                body.push('\n');
+                            }
+                            continue;
+                        }
+                        let command = line.split(' ').next().unwrap_or_default();
+                        let reply: &[u8] = match command.to_ascii_uppercase().as_str() {
+                            "EHLO" | "HELO" => b"250 sink\r\n",
+                            "DATA" => {
+                                data = Some(String::new());
+                                b"354 Go ahead\r\n"
+                            }
+                            "QUIT" => {
+                                write.write_all(b"221 Bye\r\n").await?;
+                                break;
+                            }
+                            _ => b"250 OK\r\n",
+                        };
+                        write.write_all(reply).await?;
+                    }
+                    anyhow::Ok(())
+                });
+            }
+        });
+
+        Ok((port, received))
+    }
+
+    #[test]
+    fn test_digest_lines_only_others_since() -> Result<()> {
+        let fx_since = OffsetDateTime::now_utc();
+        let fx_before = fx_since - time::Duration::hours(1);
+        let fx_after = fx_since + time::Duration::minutes(1);
+        let fx_oct_20 = Date::from_calendar_date(2026, Month::October, 20)?;
+        let fx_oct_21 = Date::from_calendar_date(2026, Month::October, 21)?;
+        let fx_users = vec![
+            (
+                fx_recipient(1, "ann", fx_before)?,
+                vec![fx_date(0, fx_oct_20, fx_after)],
+            ),
+            (
+                fx_recipient(2, "bob", fx_before)?,
+                vec![fx_date(0, fx_oct_20, fx_before)],
+            ),
+            (
+                fx_recipient(3, "carl", fx_after)?,
+                vec![
+                    fx_date(0, fx_oct_21, fx_after),
+                    fx_date(0, fx_oct_20, fx_after),
+                ],
+            ),
+            (fx_recipient(4, "dave", fx_after)?, vec![]),
+        ];
+
+        let lines = digest_lines(&fx_users, 1, fx_since, Locale::En);
+
+        assert_eq!(
+            lines,
+            vec![
+                "carl joined and picked Tuesday 20 October 2026, Wednesday 21 October 2026",
+                "dave joined",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_digest_lines_in_locale() -> Result<()> {
+        let fx_since = OffsetDateTime::now_utc();
+        let fx_after = fx_since + time::Duration::minutes(1);
+        let fx_oct_20 = Date::from_calendar_date(2026, Month::October, 20)?;
+        let fx_users = vec![(
+            fx_recipient(2, "bob", fx_since)?,
+            vec![fx_date(0, fx_oct_20, fx_after)],
+        )];
+
+        let lines = digest_lines(&fx_users, 1, fx_since, Locale::De);
+
+        assert_eq!(lines, vec!["bob wählte Dienstag 20 Oktober 2026"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_email_sent_over_smtp() -> Result<()> {
+        let (port, received) = fx_smtp_sink().await?;
+        let notifier = Notifier::new(
+            &format!("smtp://127.0.0.1:{port}"),
+            "Pick the day <noreply@example.com>",
+            "https://picktheday.example",
+        )?;
+        let mut fx_plan = fx_plan("Dinner")?;
+        fx_plan.public_id = PublicId::new("Ln64VOp4fBgSxctB")?;
+        let mut fx_user = fx_recipient(1, "ann", OffsetDateTime::now_utc())?;
+        fx_user.email_confirmed = false;
+        let token = fx_user.email_token.clone().unwrap();
+
+        let message = notifier.confirmation_email(&fx_plan, &fx_user)?.unwrap();
+        notifier.send(message).await?;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let email = &received[0];
+        // The body is quoted-printable, undo its soft line breaks and escaped `=`
+        let body = email.replace("=\n", "").replace("=3D", "=");
+        assert!(email.contains("To: ann <ann@example.com>"));
+        assert!(email.contains(&format!(
+            "List-Unsubscribe: <https://picktheday.example/plan/Ln64VOp4fBgSxctB/email/unsubscribe?user={}&token={}>",
+            fx_user.public_id,
+            token.expose()
+        )));
+        assert!(body.contains(&format!(
+            "https://picktheday.example/plan/Ln64VOp4fBgSxctB/email/confirm?user={}&token={}",
+            fx_user.public_id,
+            token.expose()
+        )));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
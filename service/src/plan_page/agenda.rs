@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use leptos::prelude::*;
+use time::Date;
+
+use crate::{
+    i18n::{t, use_locale, Text},
+    plan_page::{comments::DateNotes, ranking::ranked_dates, UserWithDates},
+};
+
+/// The picked dates from today on, with who picked them
+#[component]
+pub fn Agenda(
+    users_with_dates: Vec<UserWithDates>,
+    today: Date,
+    min_attendance: Option<usize>,
+    date_notes: DateNotes,
+) -> impl IntoView {
+    let locale = use_locale();
+    let ranked_dates = ranked_dates(&users_with_dates);
+    let agenda = agenda_dates(&users_with_dates, today);
+
+    if agenda.is_empty() {
+        return view! { <p class="text-center text-muted">{t(Text::NoDatesPicked)}</p> }.into_any();
+    }
+
+    let rows = agenda
+        .into_iter()
+        .map(|(date, names)| {
+            let availability = ranked_dates.get(&date);
+            let badge_class = format!(
+                "rounded-full px-2 text-content {}",
+                availability.heat_level().class()
+            );
+            // Dates without every required user are listed, but struck through
+            let date_class = if availability.is_viable() {
+                "text-content font-bold"
+            } else {
+                "text-faint font-bold line-through"
+            };
+            let count = format!("{}/{}", availability.count(), availability.total());
+            let tooltip = availability.tooltip(locale);
+            let notes = date_notes.get(&date).map(|notes| {
+                notes
+                    .iter()
+                    .map(|note| view! { <div>{note.clone()}</div> })
+                    .collect_view()
+            });
+            // Enough users on a non-viable date still miss the quorum, the strike through says why
+            let quorum = min_attendance.and_then(|min_attendance| {
+                match availability.needed_for_quorum(min_attendance) {
+                    _ if availability.has_quorum(min_attendance) => {
+                        Some(locale.text(Text::QuorumReached).to_string())
+                    }
+                    0 => None,
+                    needed => Some(format!("{}: {needed}", locale.text(Text::MoreNeeded))),
+                }
+            });
+
+            view! {
+                <li class="flex items-center justify-between border-b border-line py-2">
+                    <div>
+                        <div class=date_class>
+                            {locale.weekday_name(date.weekday())} " " {date.day()} " "
+                            {locale.month_name(date.month())} " " {date.year()}
+                        </div>
+                        <div class="text-sm text-muted">{names.join(", ")}</div>
+                        <div class="text-xs text-muted">{quorum}</div>
+                        <div class="text-xs italic text-muted">{notes}</div>
+                    </div>
+                    <span class=badge_class title=tooltip>
+                        {count}
+                    </span>
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! { <ul class="mx-auto max-w-md text-left">{rows}</ul> }.into_any()
+}
+
+/// Names of the users per picked date, in date order, skipping dates before `today`
+fn agenda_dates(users_with_dates: &[UserWithDates], today: Date) -> BTreeMap<Date, Vec<String>> {
+    let mut agenda: BTreeMap<Date, Vec<String>> = BTreeMap::new();
+
+    for (user, dates) in users_with_dates {
+        for date_model in dates.iter().filter(|date_model| date_model.date >= today) {
+            agenda
+                .entry(date_model.date)
+                .or_default()
+                .push(user.name.to_string());
+        }
+    }
+
+    agenda
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use time::Month;
+
+    use crate::test_support::fx_user_with_dates;
+
+    #[test]
+    fn test_agenda_dates_grouped_from_today() -> Result<()> {
+        let fx_today = Date::from_calendar_date(2026, Month::October, 19)?;
+        let fx_past = Date::from_calendar_date(2026, Month::October, 1)?;
+        let fx_tomorrow = Date::from_calendar_date(2026, Month::October, 20)?;
+        let fx_later = Date::from_calendar_date(2026, Month::November, 2)?;
+        let fx_users = vec![
+            fx_user_with_dates(0, "ann", &[fx_tomorrow, fx_past])?,
+            fx_user_with_dates(0, "bob", &[fx_later, fx_tomorrow])?,
+        ];
+
+        let agenda = agenda_dates(&fx_users, fx_today);
+
+        assert_eq!(
+            agenda.into_iter().collect::<Vec<_>>(),
+            vec![
+                (fx_tomorrow, vec!["ann".to_string(), "bob".to_string()]),
+                (fx_later, vec!["bob".to_string()]),
+            ]
+        );
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
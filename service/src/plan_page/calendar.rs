@@ -1,43 +1,64 @@
-use std::collections::HashMap;
-
 use axum::{
     debug_handler,
     extract::{Path, Query, State},
-    response::{Html, IntoResponse},
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
     Form, Router,
 };
+use axum_htmx::{HxRequest, SwapOption};
+use derive_more::derive::Display;
 use entity::{
     dates::{self},
     db::ModelManager,
+    plans,
     types::deserialize_public_id_option,
     types::PublicId,
     users,
 };
-use http::StatusCode;
-use leptos::{either::Either, prelude::*};
+use http::{HeaderMap, StatusCode, Uri};
+use leptos::prelude::*;
 use once_cell::sync::Lazy;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize};
+use serde_json::json;
 use time::{Date, Month, OffsetDateTime, Weekday};
+use time_tz::OffsetDateTimeExt;
 use tracing::debug;
 
 use crate::{
     error::Result,
-    htmx_helpers::{HtmxId, HtmxInclude, HtmxInput, HtmxTarget},
-    plan_page::{filter_users_with_dates, htmx_ids},
-    util_components::{HtmxHiddenInput, Icon},
+    events::{self, PlanEvent},
+    htmx_helpers::{Htmx, HtmxId, HtmxInclude, HtmxInput, HtmxRoute, HtmxSwapOob, HtmxTrigger},
+    http_cache::PlanVersion,
+    i18n::{t, use_locale, Locale, Text},
+    plan_page::{filter_users_with_dates, htmx_ids, plan_page_redirect},
+    request_context::RequestContext,
+    util_components::{CsrfField, HtmxHiddenInput, Icon},
+};
+
+use super::{
+    agenda::Agenda,
+    comments::{self, DateNotes, DateNotesPopover},
+    quorum,
+    ranking::{ranked_dates, DateAvailability, HeatLevel},
+    UserWithDates,
 };
 
-use super::UserWithDates;
+pub const CALENDAR_ROUTE: HtmxRoute = HtmxRoute::plan("/calendar");
+pub const DATE_ROUTE: HtmxRoute = HtmxRoute::plan("/calendar/date");
+pub const DATES_ROUTE: HtmxRoute = HtmxRoute::plan("/calendar/dates");
 
 pub fn routes(mm: ModelManager) -> Router<entity::db::ModelManager> {
-    Router::new().nest(
-        "/calendar",
-        Router::new()
-            .route("/", get(get_calendar_handler))
-            .route("/date", post(add_date_handler).delete(delete_date_handler))
-            .with_state(mm),
-    )
+    Router::new()
+        .route(CALENDAR_ROUTE.path(), get(get_calendar_handler))
+        .route(
+            DATE_ROUTE.path(),
+            post(post_date_handler).delete(delete_date_handler),
+        )
+        .route(
+            DATES_ROUTE.path(),
+            post(bulk_dates_handler).delete(clear_dates_handler),
+        )
+        .with_state(mm)
 }
 
 // region:	  --- Calendar handler
@@ -49,23 +70,46 @@ struct CalendarGet {
     year: i32,
     #[serde(deserialize_with = "deserialize_public_id_option")]
     user_public_id: Option<PublicId>,
+    #[serde(default)]
+    view: CalendarView,
 }
 
 #[debug_handler]
 async fn get_calendar_handler(
     State(mm): State<ModelManager>,
     Path(plan_public_id): Path<PublicId>,
+    uri: Uri,
+    headers: HeaderMap,
+    context: RequestContext,
     Query(calendar_get): Query<CalendarGet>,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
     debug!("{:<12} - calendar - {plan_public_id}", "HANDLER");
 
-    // -- Calendar Month
-    let calendar_month = CalendarMonth {
-        month: calendar_get.month,
-        year: calendar_get.year,
-    };
+    // -- Nothing changed since this month was last shown
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let settings = CalendarSettings::new(&plan, &context);
+    let version = PlanVersion::new(
+        &plan,
+        settings.today,
+        &[
+            &uri.to_string(),
+            &context.csrf_token.to_string(),
+            &context.locale.to_string(),
+        ],
+    );
+    if version.is_fresh(&headers) {
+        return Ok(version.not_modified());
+    }
 
-    // -- Get the users and dates
+    // -- Calendar Month
+    let calendar_month =
+        CalendarMonth::from_query(Some(calendar_get.month), Some(calendar_get.year))
+            .unwrap_or(CalendarMonth::current_month(settings.today));
+
+    // -- Get the users and dates, with the notes on them
+    let comments =
+        entity::comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
     let users_with_dates =
         users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
 
@@ -76,15 +120,19 @@ async fn get_calendar_handler(
         None
     };
 
-    let view = view! {
-        <Calendar
-            users_with_dates=users_with_dates
-            current_user_with_dates=current_user_with_dates
-            calendar_month=calendar_month
-        />
-    }
-    .to_html();
-    Ok(Html(view))
+    let view = context.render(|| {
+        view! {
+            <Calendar
+                users_with_dates=users_with_dates
+                current_user_with_dates=current_user_with_dates
+                calendar_month=calendar_month
+                calendar_view=calendar_get.view
+                settings=settings
+                date_notes=comments::date_notes(&comments)
+            />
+        }
+    });
+    Ok((version, Html(view)).into_response())
 }
 
 // endregion: --- Calendar handler
@@ -94,34 +142,273 @@ static CALENDAR_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("calendar"));
 pub fn Calendar(
     users_with_dates: Vec<UserWithDates>,
     current_user_with_dates: Option<UserWithDates>,
+    /// The first month shown
     calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    settings: CalendarSettings,
+    date_notes: DateNotes,
+    /// Set when the calendar comes along with the response of another component
+    #[prop(optional)]
+    swap_oob: HtmxSwapOob,
 ) -> impl IntoView {
     let calender_id = CALENDAR_ID.clone().to_string();
+    let interactive = current_user_with_dates.is_some();
+    let user_public_id = current_user_with_dates
+        .as_ref()
+        .map(|(user, _)| user.public_id.clone());
+
+    let content = if calendar_view == CalendarView::List {
+        view! {
+            <Agenda
+                users_with_dates=users_with_dates
+                today=settings.today
+                min_attendance=settings.min_attendance
+                date_notes=date_notes
+            />
+        }
+        .into_any()
+    } else {
+        let month_count = calendar_view.month_count();
+        let mut wrapper_class = match month_count {
+            2 => "grid gap-8 lg:grid-cols-2",
+            3 => "grid gap-8 lg:grid-cols-3",
+            _ => "",
+        }
+        .to_string();
+        // Dragging across dates shouldn't select their text
+        if interactive {
+            wrapper_class += " select-none";
+        }
+
+        let months = std::iter::successors(Some(calendar_month), |month| Some(next_month(*month)))
+            .take(month_count)
+            .enumerate()
+            .map(|(index, month)| {
+                view! {
+                    <MonthGrid
+                        users_with_dates=users_with_dates.clone()
+                        current_user_with_dates=current_user_with_dates.clone()
+                        calendar_month=month
+                        first_month=calendar_month
+                        calendar_view=calendar_view
+                        settings=settings
+                        show_previous=index == 0
+                        show_next=index + 1 == month_count
+                        date_notes=date_notes.clone()
+                    />
+                }
+            })
+            .collect_view();
+
+        view! {
+            <div
+                class=wrapper_class
+                // Dragging across dates, from public/calendar.js
+                x-data="dragRange"
+                x-on:pointerdown="start"
+                x-on:pointerover="extend"
+                x-on:pointerup="finish"
+                x-on:pointerleave="cancel"
+            >
+                {months}
+                {interactive.then(|| view! { <RangeForm/> })}
+            </div>
+            <HeatmapLegend min_attendance=settings.min_attendance/>
+            {interactive.then(|| view! { <ClearDatesButton/> })}
+        }
+        .into_any()
+    };
 
     view! {
-        <div id=calender_id.clone() class="container mx-auto my-8">
-            <div class="grid grid-cols-7 gap-1 items-center justify-center">
-                <SwitchMonthButton
-                    next_or_previous=SwitchMonth::Previous
-                    calendar_month=calendar_month
-                />
-                <div class="text-lg col-span-5 font-bold text-center">
-                    {calendar_month.month.to_string()} " " {calendar_month.year}
+        <div
+            id=calender_id.clone()
+            class="container mx-auto my-8"
+            hx-swap-oob=swap_oob.attribute()
+        >
+            <HtmxHiddenInput input=htmx_ids::CALENDAR_MONTH.clone() value=calendar_month.month/>
+            <HtmxHiddenInput input=htmx_ids::CALENDAR_YEAR.clone() value=calendar_month.year/>
+            <HtmxHiddenInput input=htmx_ids::CALENDAR_VIEW.clone() value=calendar_view/>
+            <CalendarViewSwitch
+                calendar_month=calendar_month
+                calendar_view=calendar_view
+                user_public_id=user_public_id
+            />
+            {content}
+        </div>
+    }
+}
+
+/// A single month, with the buttons to move all shown months on the outer ones
+#[component]
+fn MonthGrid(
+    users_with_dates: Vec<UserWithDates>,
+    current_user_with_dates: Option<UserWithDates>,
+    calendar_month: CalendarMonth,
+    /// The first month shown, which the navigation moves from
+    first_month: CalendarMonth,
+    calendar_view: CalendarView,
+    settings: CalendarSettings,
+    show_previous: bool,
+    show_next: bool,
+    date_notes: DateNotes,
+) -> impl IntoView {
+    // The week numbers and the week buttons need an extra column in every row
+    let interactive = current_user_with_dates.is_some();
+    let user_public_id = current_user_with_dates
+        .as_ref()
+        .map(|(user, _)| user.public_id.clone());
+    let row_column = settings.show_week_numbers || interactive;
+    let title_class = if row_column {
+        "text-lg col-span-6 font-bold text-center"
+    } else {
+        "text-lg col-span-5 font-bold text-center"
+    };
+    let month_name = format!(
+        "{} {}",
+        use_locale().month_name(calendar_month.month),
+        calendar_month.year
+    );
+
+    let previous_button = if show_previous {
+        view! {
+            <SwitchMonthButton
+                next_or_previous=SwitchMonth::Previous
+                calendar_month=first_month
+                calendar_view=calendar_view
+                user_public_id=user_public_id.clone()
+            />
+        }
+        .into_any()
+    } else {
+        view! { <div></div> }.into_any()
+    };
+    let next_button = if show_next {
+        view! {
+            <SwitchMonthButton
+                next_or_previous=SwitchMonth::Next
+                calendar_month=first_month
+                calendar_view=calendar_view
+                user_public_id=user_public_id
+            />
+        }
+        .into_any()
+    } else {
+        view! { <div></div> }.into_any()
+    };
+
+    view! {
+        <div>
+            <div class=row_class(row_column)>
+                {previous_button}
+                <h2 class=title_class>{month_name.clone()}</h2>
+                {next_button}
+            </div>
+            <div
+                role="grid"
+                aria-label=month_name
+                aria-readonly=(!interactive).then_some("true")
+                class="mt-2 grid gap-1"
+                // Keyboard navigation, from public/calendar.js
+                x-data="calendarGrid"
+                x-on:keydown="move"
+                x-on:focusin="rove"
+            >
+                <div role="row" class=format!("{} border-b-2 border-muted pb-1", row_class(row_column))>
+                    <Weekdays settings=settings row_column=row_column/>
                 </div>
-                <SwitchMonthButton
-                    next_or_previous=SwitchMonth::Next
-                    calendar_month=calendar_month
-                />
-                <div class="col-span-7"></div>
-                <Weekdays/>
-                <div class="col-span-7 border-b-2 border-gray-400"></div>
                 <Dates
                     users_with_dates=users_with_dates
                     current_user_with_dates=current_user_with_dates
                     calendar_month=calendar_month
+                    first_month=first_month
+                    calendar_view=calendar_view
+                    settings=settings
+                    date_notes=date_notes
+                    row_column=row_column
                 />
             </div>
         </div>
+        {interactive.then(|| view! { <MonthButtons calendar_month=calendar_month/> })}
+    }
+}
+
+/// Columns of a row of the grid, the week numbers or buttons take an extra one
+fn row_class(row_column: bool) -> &'static str {
+    if row_column {
+        "grid grid-cols-8 gap-1 items-center justify-center"
+    } else {
+        "grid grid-cols-7 gap-1 items-center justify-center"
+    }
+}
+
+/// Links between the month, multi-month and list views
+#[component]
+fn CalendarViewSwitch(
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    /// Kept in the links, which load the whole page without htmx
+    user_public_id: Option<PublicId>,
+) -> impl IntoView {
+    let include_targets = HtmxInclude::from(vec![
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::USER_PUBLIC_ID.clone(),
+    ]);
+
+    let buttons = CalendarView::ALL
+        .into_iter()
+        .map(|option| {
+            let class = if option == calendar_view {
+                "text-content font-bold"
+            } else {
+                "text-muted hover:text-content"
+            };
+
+            view! {
+                <a
+                    href=calendar_month.page_query(option, user_public_id.as_ref())
+                    {..Htmx::get(CALENDAR_ROUTE)
+                        .target(CALENDAR_ID.clone())
+                        .swap(SwapOption::OuterHtml)
+                        .include(include_targets.clone())
+                        .vals(json!({ "view": option.to_string() }))
+                        .push_url(calendar_month.query_string(option))
+                        .attrs()}
+                    class=class
+                >
+                    {t(option.text())}
+                </a>
+            }
+        })
+        .collect_view();
+
+    view! { <div class="mb-4 flex justify-center space-x-4 text-sm">{buttons}</div> }
+}
+
+/// Ring around the dates that reached the plan's minimum attendance
+const QUORUM_CLASS: &str = " ring-2 ring-quorum";
+
+/// Explains the colors of the dates, from few to everyone available
+#[component]
+fn HeatmapLegend(min_attendance: Option<usize>) -> impl IntoView {
+    let swatches = HeatLevel::LEGEND
+        .into_iter()
+        .map(|heat_level| view! { <span class=format!("h-4 w-4 rounded-full {}", heat_level.class())></span> })
+        .collect_view();
+    let quorum = min_attendance.map(|min_attendance| {
+        view! {
+            <span class=format!("ml-4 h-4 w-4 rounded-full{QUORUM_CLASS}")></span>
+            <span>{format!("{} ({min_attendance})", t(Text::Quorum))}</span>
+        }
+    });
+
+    view! {
+        <div class="mt-4 flex items-center justify-center space-x-2 text-sm text-muted">
+            <span>{t(Text::HeatmapFew)}</span>
+            {swatches}
+            <span>{t(Text::HeatmapEveryone)}</span>
+            {quorum}
+        </div>
     }
 }
 
@@ -132,25 +419,68 @@ struct ToggleDate {
     #[serde(with = "date_format")]
     date: Date,
     user_public_id: PublicId,
+    /// Plain forms can't send a DELETE, so they post `available=false` to clear a date
+    #[serde(default = "default_available")]
+    available: bool,
+    /// Whether the date is in the month of its grid, for re-rendering it
+    #[serde(default)]
+    in_month: bool,
+    // -- The first month of the page, to go back to without htmx
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+fn default_available() -> bool {
+    true
 }
 
-async fn add_date_handler(
+async fn post_date_handler(
     State(mm): State<ModelManager>,
     Path(plan_public_id): Path<PublicId>,
+    HxRequest(htmx): HxRequest,
+    context: RequestContext,
     Form(date_post): Form<ToggleDate>,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
     debug!(
-        "{:<12} - add_date - {plan_public_id} - {}",
-        "HANDLER", date_post.date
+        "{:<12} - post_date - {plan_public_id} - {} - {}",
+        "HANDLER", date_post.date, date_post.available
     );
-    dates::helpers::user_add_date(date_post.user_public_id, date_post.date, mm).await?;
 
-    Ok((StatusCode::CREATED).into_response())
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    users::helpers::user_in_plan(plan.id, date_post.user_public_id.clone(), mm.clone()).await?;
+
+    match date_post.available {
+        true => add_date(&plan_public_id, &date_post, mm.clone()).await?,
+        false => delete_date(&plan_public_id, &date_post, mm.clone()).await?,
+    }
+
+    // -- Without htmx the whole page is loaded again
+    if !htmx {
+        let calendar_month = CalendarMonth::from_query(date_post.month, date_post.year);
+        return Ok(plan_page_redirect(
+            &plan_public_id,
+            &date_post.user_public_id,
+            calendar_month,
+            date_post.view,
+        )
+        .into_response());
+    }
+
+    let status = match date_post.available {
+        true => StatusCode::CREATED,
+        false => StatusCode::OK,
+    };
+    let view = render_date(plan_public_id, date_post, context, mm).await?;
+    Ok((status, view).into_response())
 }
 
 async fn delete_date_handler(
     State(mm): State<ModelManager>,
     Path(plan_public_id): Path<PublicId>,
+    context: RequestContext,
     Query(date_delete): Query<ToggleDate>,
 ) -> Result<impl IntoResponse> {
     debug!(
@@ -158,12 +488,296 @@ async fn delete_date_handler(
         "HANDLER", date_delete.date
     );
 
-    dates::helpers::user_delete_date(date_delete.user_public_id, date_delete.date, mm).await?;
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    users::helpers::user_in_plan(plan.id, date_delete.user_public_id.clone(), mm.clone()).await?;
 
-    Ok((StatusCode::OK).into_response())
+    delete_date(&plan_public_id, &date_delete, mm.clone()).await?;
+
+    let view = render_date(plan_public_id, date_delete, context, mm).await?;
+    Ok((StatusCode::OK, view).into_response())
+}
+
+async fn add_date(
+    plan_public_id: &PublicId,
+    toggle_date: &ToggleDate,
+    mm: ModelManager,
+) -> Result<()> {
+    dates::helpers::user_add_date(
+        toggle_date.user_public_id.clone(),
+        toggle_date.date,
+        mm.clone(),
+    )
+    .await?;
+    events::publish(PlanEvent::AvailabilityChanged {
+        plan_public_id: plan_public_id.clone(),
+        user_public_id: toggle_date.user_public_id.clone(),
+        dates: vec![toggle_date.date],
+        available: true,
+    });
+    quorum::publish_new_quorums(plan_public_id.clone(), mm).await?;
+
+    Ok(())
+}
+
+async fn delete_date(
+    plan_public_id: &PublicId,
+    toggle_date: &ToggleDate,
+    mm: ModelManager,
+) -> Result<()> {
+    dates::helpers::user_delete_date(toggle_date.user_public_id.clone(), toggle_date.date, mm)
+        .await?;
+    events::publish(PlanEvent::AvailabilityChanged {
+        plan_public_id: plan_public_id.clone(),
+        user_public_id: toggle_date.user_public_id.clone(),
+        dates: vec![toggle_date.date],
+        available: false,
+    });
+
+    Ok(())
+}
+
+/// The toggled date on its own, keeping the focus on it
+async fn render_date(
+    plan_public_id: PublicId,
+    toggle_date: ToggleDate,
+    context: RequestContext,
+    mm: ModelManager,
+) -> Result<Html<String>> {
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let settings = CalendarSettings::new(&plan, &context);
+    let comments =
+        entity::comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+
+    let date = toggle_date.date;
+    let selected = filter_users_with_dates(&users_with_dates, toggle_date.user_public_id.clone())
+        .is_some_and(|(_, dates)| dates.iter().any(|date_model| date_model.date == date));
+    let other_users =
+        crate::plan_page::remove_user(users_with_dates, toggle_date.user_public_id.clone());
+    let first_month = CalendarMonth::from_query(toggle_date.month, toggle_date.year)
+        .unwrap_or(CalendarMonth::current_month(date));
+    let notes = comments::date_notes(&comments)
+        .remove(&date)
+        .unwrap_or_default();
+
+    Ok(Html(context.render(|| {
+        view! {
+            <InteractiveDate
+                date=date
+                today=settings.today
+                in_month=toggle_date.in_month
+                selected=selected
+                tab_stop=true
+                autofocus=true
+                others_availability=ranked_dates(&other_users).get(&date)
+                notes=notes
+                user_public_id=toggle_date.user_public_id.clone()
+                first_month=first_month
+                calendar_view=toggle_date.view
+            />
+        }
+    })))
 }
 
 // endregion: --- Date handlers
+// region:	  --- Bulk date handlers
+
+/// Which days of a range a bulk operation is about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum DayFilter {
+    #[default]
+    #[display("all")]
+    All,
+    #[display("weekdays")]
+    Weekdays,
+    #[display("weekends")]
+    Weekends,
+}
+
+impl DayFilter {
+    fn matches(self, date: Date) -> bool {
+        let weekend = matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday);
+
+        match self {
+            DayFilter::All => true,
+            DayFilter::Weekdays => !weekend,
+            DayFilter::Weekends => weekend,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkDatesPost {
+    user_public_id: PublicId,
+    // -- The range, in either order
+    #[serde(with = "date_format")]
+    from: Date,
+    #[serde(with = "date_format")]
+    to: Date,
+    #[serde(default)]
+    days: DayFilter,
+    /// Clears the dates instead of picking them
+    #[serde(default)]
+    clear: bool,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+async fn bulk_dates_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    context: RequestContext,
+    Form(bulk_post): Form<BulkDatesPost>,
+) -> Result<Response> {
+    debug!(
+        "{:<12} - bulk_dates - {plan_public_id} - {} to {} - {}",
+        "HANDLER", bulk_post.from, bulk_post.to, bulk_post.days
+    );
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let user = users::helpers::user_in_plan(plan.id, bulk_post.user_public_id, mm.clone()).await?;
+    let settings = CalendarSettings::new(&plan, &context);
+
+    let range = dates_in_range(bulk_post.from, bulk_post.to, bulk_post.days);
+    if bulk_post.clear {
+        let removed =
+            dates::helpers::user_delete_dates(user.public_id.clone(), range, mm.clone()).await?;
+        publish_availability(&plan_public_id, &user.public_id, removed, false);
+    } else {
+        // Past dates are only picked one at a time
+        let upcoming = range
+            .into_iter()
+            .filter(|date| *date >= settings.today)
+            .collect();
+        let added =
+            dates::helpers::user_add_dates(user.public_id.clone(), upcoming, mm.clone()).await?;
+        publish_availability(&plan_public_id, &user.public_id, added, true);
+        quorum::publish_new_quorums(plan_public_id.clone(), mm.clone()).await?;
+    }
+
+    let calendar_month = CalendarMonth::from_query(bulk_post.month, bulk_post.year);
+    render_calendar(
+        plan_public_id,
+        user.public_id,
+        calendar_month,
+        bulk_post.view,
+        settings,
+        context,
+        mm,
+    )
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+struct ClearDates {
+    user_public_id: PublicId,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+async fn clear_dates_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    context: RequestContext,
+    Query(clear_dates): Query<ClearDates>,
+) -> Result<Response> {
+    debug!("{:<12} - clear_dates - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let user =
+        users::helpers::user_in_plan(plan.id, clear_dates.user_public_id, mm.clone()).await?;
+
+    let removed = dates::helpers::user_clear_dates(user.public_id.clone(), mm.clone()).await?;
+    publish_availability(&plan_public_id, &user.public_id, removed, false);
+
+    let calendar_month = CalendarMonth::from_query(clear_dates.month, clear_dates.year);
+    render_calendar(
+        plan_public_id,
+        user.public_id,
+        calendar_month,
+        clear_dates.view,
+        CalendarSettings::new(&plan, &context),
+        context,
+        mm,
+    )
+    .await
+}
+
+/// One event for the whole change, none when nothing changed
+fn publish_availability(
+    plan_public_id: &PublicId,
+    user_public_id: &PublicId,
+    dates: Vec<Date>,
+    available: bool,
+) {
+    if dates.is_empty() {
+        return;
+    }
+    events::publish(PlanEvent::AvailabilityChanged {
+        plan_public_id: plan_public_id.clone(),
+        user_public_id: user_public_id.clone(),
+        dates,
+        available,
+    });
+}
+
+/// The calendar of the user after a bulk change
+async fn render_calendar(
+    plan_public_id: PublicId,
+    user_public_id: PublicId,
+    calendar_month: Option<CalendarMonth>,
+    calendar_view: CalendarView,
+    settings: CalendarSettings,
+    context: RequestContext,
+    mm: ModelManager,
+) -> Result<Response> {
+    let comments =
+        entity::comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+    let current_user_with_dates = filter_users_with_dates(&users_with_dates, user_public_id);
+    let calendar_month =
+        calendar_month.unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &settings));
+
+    let view = context.render(|| {
+        view! {
+            <Calendar
+                users_with_dates=users_with_dates
+                current_user_with_dates=current_user_with_dates
+                calendar_month=calendar_month
+                calendar_view=calendar_view
+                settings=settings
+                date_notes=comments::date_notes(&comments)
+            />
+        }
+    });
+    Ok(Html(view).into_response())
+}
+
+/// The days from one date to the other, at most as many as a user can pick
+fn dates_in_range(from: Date, to: Date, days: DayFilter) -> Vec<Date> {
+    let (first, last) = if from <= to { (from, to) } else { (to, from) };
+
+    std::iter::successors(Some(first), |date| date.next_day())
+        .take_while(|date| *date <= last)
+        .take(dates::MAX_DATES_PER_USER as usize)
+        .filter(|date| days.matches(*date))
+        .collect()
+}
+
+// endregion: --- Bulk date handlers
 
 /// A list of dates that are padded to fit a 7 day calendar
 #[component]
@@ -174,162 +788,436 @@ fn Dates(
     users_with_dates: Vec<UserWithDates>,
     /// Calendar month to be displayed
     calendar_month: CalendarMonth,
+    /// The first month shown, which the date forms go back to without htmx
+    first_month: CalendarMonth,
+    calendar_view: CalendarView,
+    settings: CalendarSettings,
+    /// Shown on hover or tap of their date
+    date_notes: DateNotes,
+    /// The rows start with their week number or week button
+    row_column: bool,
 ) -> impl IntoView {
-    if let Some((user, dates)) = current_user_with_dates {
-        let other_users = crate::plan_page::remove_user(users_with_dates, user.public_id);
-        let ranked_dates = ranked_dates(&other_users);
-        // Get the dates for the user
-        Either::Left(
-            calendar_month
-                .dates()
-                .into_iter()
-                .map(|date| {
-                    let others_date_rank = if let Some(rank) = ranked_dates.get(&date) {
-                        *rank
-                    } else {
-                        DateRank::None
-                    };
-                    let selected = dates.iter().any(|date_model| date == date_model.date);
+    let notes = move |date: Date| date_notes.get(&date).cloned().unwrap_or_default();
+    let picked: Option<Vec<Date>> = current_user_with_dates
+        .as_ref()
+        .map(|(_, dates)| dates.iter().map(|date_model| date_model.date).collect());
+    // Tab enters the grid on today, or on the first of the month when today isn't in it
+    let tab_stop = if CalendarMonth::current_month(settings.today) == calendar_month {
+        settings.today
+    } else {
+        calendar_month
+            .month_dates()
+            .first()
+            .copied()
+            .unwrap_or(settings.today)
+    };
+    let in_month = move |date: Date| date.month() == calendar_month.month;
+
+    let date_view: Box<dyn Fn(Date) -> AnyView> =
+        if let Some((user, dates)) = current_user_with_dates {
+            let user_public_id = user.public_id.clone();
+            let other_users = crate::plan_page::remove_user(users_with_dates, user.public_id);
+            let ranked_dates = ranked_dates(&other_users);
+            // Get the dates for the user
+            Box::new(move |date| {
+                let others_availability = ranked_dates.get(&date);
+                let selected = dates.iter().any(|date_model| date == date_model.date);
+
+                view! {
+                    <InteractiveDate
+                        date=date
+                        today=settings.today
+                        in_month=in_month(date)
+                        selected=selected
+                        tab_stop=date == tab_stop && in_month(date)
+                        autofocus=false
+                        others_availability=others_availability
+                        notes=notes(date)
+                        user_public_id=user_public_id.clone()
+                        first_month=first_month
+                        calendar_view=calendar_view
+                    />
+                }
+                .into_any()
+            })
+        } else {
+            let ranked_dates = ranked_dates(&users_with_dates);
+            Box::new(move |date| {
+                let availability = ranked_dates.get(&date);
+
+                view! {
+                    <NonInteractiveDate
+                        date=date
+                        today=settings.today
+                        in_month=in_month(date)
+                        tab_stop=date == tab_stop && in_month(date)
+                        availability=availability
+                        min_attendance=settings.min_attendance
+                        notes=notes(date)
+                    />
+                }
+                .into_any()
+            })
+        };
 
+    calendar_month
+        .dates(settings.week_start)
+        .chunks(7)
+        .map(|week| {
+            let week_number = settings.show_week_numbers.then(|| iso_week(week));
+            let row_start = match &picked {
+                Some(picked) => Some(
                     view! {
-                        <InteractiveDate
-                            date=date
-                            calendar_month=calendar_month
-                            selected=selected
-                            others_date_rank=others_date_rank
-                        />
+                        <div role="rowheader">
+                            <WeekButton
+                                week=week.to_vec()
+                                all_picked=week.iter().all(|date| picked.contains(date))
+                                week_number=week_number
+                            />
+                        </div>
                     }
-                })
-                .collect_view(),
-        )
-    } else {
-        let ranked_dates = ranked_dates(&users_with_dates);
-        Either::Right(
-            calendar_month
-                .dates()
-                .into_iter()
-                .map(|date| {
-                    let date_rank = if let Some(rank) = ranked_dates.get(&date) {
-                        *rank
-                    } else {
-                        DateRank::None
-                    };
-
-                    view! { <NonInteractiveDate date=date calendar_month=calendar_month date_rank=date_rank/> }
-                })
-                .collect_view(),
-        )
+                    .into_any(),
+                ),
+                None => week_number.map(|week_number| {
+                    view! {
+                        <div role="rowheader" class="text-sm text-faint">
+                            {week_number}
+                        </div>
+                    }
+                    .into_any()
+                }),
+            };
+
+            view! {
+                <div role="row" class=row_class(row_column)>
+                    {row_start}
+                    {week.iter().map(|date| date_view(*date)).collect_view()}
+                </div>
+            }
+        })
+        .collect_view()
+}
+
+/// Full date with the availability on it, for screen readers
+fn date_label(date: Date, availability: &DateAvailability, locale: Locale) -> String {
+    let mut label = format!(
+        "{} {} {} {}",
+        locale.weekday_name(date.weekday()),
+        date.day(),
+        locale.month_name(date.month()),
+        date.year()
+    );
+
+    if availability.total() > 0 {
+        label += &format!(
+            ", {}: {}/{}",
+            locale.text(Text::Available),
+            availability.count(),
+            availability.total()
+        );
+    }
+    if !availability.is_viable() {
+        label += &format!(", {}", locale.text(Text::MissingRequired));
     }
+
+    label
 }
 
 #[component]
 fn NonInteractiveDate(
     date: Date,
-    calendar_month: CalendarMonth,
-    date_rank: DateRank,
+    today: Date,
+    /// Dates of the neighbouring months are muted
+    in_month: bool,
+    /// In the tab order of the grid
+    tab_stop: bool,
+    availability: DateAvailability,
+    min_attendance: Option<usize>,
+    notes: Vec<String>,
 ) -> impl IntoView {
-    let mut class = "relative h-12 w-full flex items-center justify-center".to_string();
+    let mut class = "group relative h-12 w-full flex items-center justify-center".to_string();
 
-    if is_today(date) {
-        class += " ring-sky-500 ring-1"
+    if date == today {
+        class += " ring-accent ring-1"
     }
 
-    if date.month() == calendar_month.month {
-        class += " text-white"
+    if in_month {
+        class += " text-content"
     } else {
-        class += " text-gray-500"
+        class += " text-faint"
     }
 
     let mut selected_class = "absolute top-1/2 left-1/2  transform -translate-x-1/2 -translate-y-1/2  w-10 h-10 rounded-full z-0".to_string();
 
-    match date_rank {
-        DateRank::All => selected_class += " bg-slate-500",
-        DateRank::Some => selected_class += " bg-slate-700",
-        DateRank::None => selected_class = "".to_string(),
+    match availability.heat_level() {
+        HeatLevel::None => selected_class = "".to_string(),
+        heat_level => selected_class = format!("{selected_class} {}", heat_level.class()),
+    }
+
+    if min_attendance.is_some_and(|min_attendance| availability.has_quorum(min_attendance)) {
+        selected_class += QUORUM_CLASS;
     }
 
+    let locale = use_locale();
+    let tooltip = availability.tooltip(locale);
+    let mut label = date_label(date, &availability, locale);
+    for note in &notes {
+        label += &format!(", {note}");
+    }
+    // Focusable, so tapping the date shows its notes
+    let tabindex = if tab_stop { "0" } else { "-1" };
+
     view! {
-        <div class=class>
+        <div
+            role="gridcell"
+            class=class
+            title=tooltip
+            tabindex=tabindex
+            aria-label=label
+            data-cell=""
+        >
             <span class=selected_class></span>
-            <span class="relative z-10">{date.day()}</span>
+            <span class="relative z-10" aria-hidden="true">
+                {date.day()}
+            </span>
+            <DateNotesPopover notes=notes/>
         </div>
     }
 }
 
+/// Picks or clears the date for the current user, the response replaces the date.
+///
+/// A form of its own, so it also works without htmx.
 #[component]
 fn InteractiveDate(
     date: Date,
+    today: Date,
+    /// Dates of the neighbouring months are muted
+    in_month: bool,
     selected: bool,
-    calendar_month: CalendarMonth,
-    others_date_rank: DateRank,
+    /// In the tab order of the grid
+    tab_stop: bool,
+    /// Focuses the date once it is swapped in
+    autofocus: bool,
+    /// Availability of everyone but the current user
+    others_availability: DateAvailability,
+    notes: Vec<String>,
+    user_public_id: PublicId,
+    // -- The page to go back to without htmx
+    first_month: CalendarMonth,
+    calendar_view: CalendarView,
 ) -> impl IntoView {
-    let user_public_id = htmx_ids::USER_PUBLIC_ID.clone();
-    let date_button_id = HtmxInput::new(HtmxId::new(&format!("date-{}", date)), "date");
-
-    let include_targets =
-        HtmxInclude::from(vec![user_public_id, date_button_id.clone()]).to_string();
-
     // TODO: Think of how to improve class composing in a less ad-hoc way
     let mut class = "relative h-12 w-full".to_string();
 
-    if is_today(date) {
-        class += " ring-sky-500 ring-1 hover:ring-gray-400"
+    if date == today {
+        class += " ring-accent ring-1 hover:ring-muted"
     } else {
-        class += " ring-gray-400 hover:ring-1"
+        class += " ring-muted hover:ring-1"
     }
 
-    if date.month() == calendar_month.month {
-        class += " text-white"
+    if in_month {
+        class += " text-content"
     } else {
-        class += " text-gray-500"
+        class += " text-faint"
     }
 
-    let mut selected_class = class.clone();
-
-    match others_date_rank {
-        DateRank::All => selected_class += " bg-slate-500",
-        _ => selected_class += " bg-slate-700",
+    // Picked dates are marked by more than their color
+    if selected {
+        class += " bg-raised font-bold underline"
     }
-    let xdata = if selected {
-        "{isDelete : true}"
-    } else {
-        "{isDelete : false}"
-    };
 
     let mut others_selected_class = "absolute top-1/2 left-1/2  transform -translate-x-1/2 -translate-y-1/2  w-12 h-12 rounded-full z-0".to_string();
 
-    match others_date_rank {
-        DateRank::All => others_selected_class += " bg-slate-500",
-        DateRank::Some => others_selected_class += " bg-slate-700",
-        DateRank::None => others_selected_class = "".to_string(),
+    match others_availability.heat_level() {
+        HeatLevel::None => others_selected_class = "".to_string(),
+        heat_level => {
+            others_selected_class = format!("{others_selected_class} {}", heat_level.class())
+        }
+    }
+
+    let locale = use_locale();
+    let tooltip = others_availability.tooltip(locale);
+    let mut label = date_label(date, &others_availability, locale);
+    for note in &notes {
+        label += &format!(", {note}");
     }
+    let check_mark = selected.then(|| {
+        view! {
+            <span class="absolute left-1 top-0 z-10 text-xs" aria-hidden="true">
+                "✓"
+            </span>
+        }
+    });
+
+    view! {
+        <div
+            role="gridcell"
+            title=tooltip
+            class="group relative"
+            data-date=date.to_string()
+            data-picked=selected.to_string()
+        >
+            <form
+                method="post"
+                action=DATE_ROUTE.to_string()
+                {..Htmx::post(DATE_ROUTE)
+                    .target_closest("[role='gridcell']")
+                    .swap(SwapOption::OuterHtml)
+                    .attrs()}
+            >
+                <CsrfField/>
+                <input type="hidden" name="date" value=date.to_string()/>
+                <input type="hidden" name="available" value=(!selected).to_string()/>
+                <input type="hidden" name="in_month" value=in_month.to_string()/>
+                <input type="hidden" name="user_public_id" value=user_public_id.to_string()/>
+                <input type="hidden" name="month" value=first_month.month.to_string()/>
+                <input type="hidden" name="year" value=first_month.year/>
+                <input type="hidden" name="view" value=calendar_view.to_string()/>
+                <button
+                    type="submit"
+                    class=class
+                    tabindex=if tab_stop { "0" } else { "-1" }
+                    autofocus=autofocus
+                    aria-pressed=selected.to_string()
+                    aria-label=label
+                    data-cell=""
+                >
+                    <span class=others_selected_class></span>
+                    {check_mark}
+                    <span class="relative z-10" aria-hidden="true">
+                        {date.day()}
+                    </span>
+                </button>
+            </form>
+            <DateNotesPopover notes=notes/>
+        </div>
+    }
+}
+
+/// Bulk operations send the user and month along, and re-render the whole calendar
+fn bulk_dates(htmx: Htmx) -> Htmx {
+    htmx.include(vec![
+        htmx_ids::USER_PUBLIC_ID.clone(),
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::CALENDAR_VIEW.clone(),
+    ])
+    .target(CALENDAR_ID.clone())
+    .swap(SwapOption::OuterHtml)
+}
+
+/// Submitted when a drag across dates ends
+#[component]
+fn RangeForm() -> impl IntoView {
+    view! {
+        <form
+            x-ref="range"
+            class="hidden"
+            // Sent by Alpine at the end of a drag
+            {..bulk_dates(Htmx::post(DATES_ROUTE)).trigger(HtmxTrigger::Submit).attrs()}
+        >
+            <input type="hidden" name="from" x-bind:value="from"/>
+            <input type="hidden" name="to" x-bind:value="to"/>
+            <input type="hidden" name="clear" x-bind:value="clear"/>
+        </form>
+    }
+}
+
+/// Picks the whole row, or clears it when every date in it is picked
+#[component]
+fn WeekButton(week: Vec<Date>, all_picked: bool, week_number: Option<u8>) -> impl IntoView {
+    let (from, to) = (week.first().copied(), week.last().copied());
+    let vals = match (from, to) {
+        (Some(from), Some(to)) => {
+            json!({
+                "from": from.to_string(),
+                "to": to.to_string(),
+                "clear": all_picked.to_string(),
+            })
+        }
+        _ => json!({}),
+    };
+    let sign = if all_picked { "−" } else { "+" };
+    let label = week_number
+        .map(|week_number| week_number.to_string())
+        .unwrap_or(sign.to_string());
+    let (class, title) = if all_picked {
+        (
+            "h-8 w-8 rounded-full text-sm text-content bg-raised ring-muted hover:ring-1",
+            t(Text::ClearWeek),
+        )
+    } else {
+        (
+            "h-8 w-8 rounded-full text-sm text-faint ring-muted hover:ring-1",
+            t(Text::SelectWeek),
+        )
+    };
 
     view! {
-        <div x-data=xdata>
-            <HtmxHiddenInput input=date_button_id value=date/>
+        <button
+            type="button"
+            {..bulk_dates(Htmx::post(DATES_ROUTE)).vals(vals).attrs()}
+            class=class
+            title=title
+        >
+            {label}
+        </button>
+    }
+}
+
+/// Picks every weekday or weekend day of the month
+#[component]
+fn MonthButtons(calendar_month: CalendarMonth) -> impl IntoView {
+    let month_dates = calendar_month.month_dates();
+    let (Some(first), Some(last)) = (month_dates.first(), month_dates.last()) else {
+        return ().into_any();
+    };
+
+    let buttons = [
+        (DayFilter::Weekdays, Text::SelectWeekdays),
+        (DayFilter::Weekends, Text::SelectWeekends),
+    ]
+    .into_iter()
+    .map(|(days, label)| {
+        view! {
             <button
-                x-show="!isDelete"
-                hx-include=include_targets.clone()
-                hx-swap="none"
                 type="button"
-                class=class
-                x-on:click="isDelete = !isDelete"
-                hx-post="calendar/date"
+                class="rounded-full border border-line px-2 hover:text-content"
+                {..bulk_dates(Htmx::post(DATES_ROUTE))
+                    .vals(
+                        json!({
+                            "from": first.to_string(),
+                            "to": last.to_string(),
+                            "days": days.to_string(),
+                        }),
+                    )
+                    .attrs()}
             >
-                <span class=others_selected_class.clone()></span>
-                <span class="relative z-10">{date.day()}</span>
+                {t(label)}
             </button>
+        }
+    })
+    .collect_view();
+
+    view! { <div class="mt-2 flex justify-center space-x-2 text-xs text-muted">{buttons}</div> }
+        .into_any()
+}
+
+/// Removes every date of the current user, after asking
+#[component]
+fn ClearDatesButton() -> impl IntoView {
+    view! {
+        <div class="mt-2 text-center text-xs text-muted">
+            <p>{t(Text::DragHint)}</p>
             <button
-                x-show="isDelete"
-                hx-include=include_targets
-                hx-swap="none"
                 type="button"
-                class=selected_class
-                x-on:click="isDelete = !isDelete"
-                hx-delete="calendar/date"
+                class="mt-1 underline hover:text-content"
+                {..bulk_dates(Htmx::delete(DATES_ROUTE))
+                    .confirm(t(Text::ClearAllDatesConfirm))
+                    .attrs()}
             >
-                <span class=others_selected_class></span>
-                <span class="relative z-10">{date.day()}</span>
+                {t(Text::ClearAllDates)}
             </button>
         </div>
     }
@@ -344,6 +1232,9 @@ enum SwitchMonth {
 fn SwitchMonthButton(
     next_or_previous: SwitchMonth,
     calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    /// Kept in the link, which loads the whole page without htmx
+    user_public_id: Option<PublicId>,
 ) -> impl IntoView {
     let (switch_month_id, switch_year_id, switch_calendar_month, button_icon) =
         match next_or_previous {
@@ -361,73 +1252,203 @@ fn SwitchMonthButton(
             ),
         };
 
-    let calendar_target = HtmxTarget::from(CALENDAR_ID.clone()).to_string();
     let include_targets = HtmxInclude::from(vec![
         switch_month_id.clone(),
         switch_year_id.clone(),
         htmx_ids::USER_PUBLIC_ID.clone(),
-    ])
-    .to_string();
+        htmx_ids::CALENDAR_VIEW.clone(),
+    ]);
 
     view! {
         <HtmxHiddenInput input=switch_month_id value=switch_calendar_month.month/>
         <HtmxHiddenInput input=switch_year_id value=switch_calendar_month.year/>
-        <button
-            hx-get="calendar"
-            hx-swap="outerHTML"
-            hx-include=include_targets
-            hx-target=calendar_target
+        // Keep the month in the page url, so reloading or sharing it opens the same month
+        <a
+            href=switch_calendar_month.page_query(calendar_view, user_public_id.as_ref())
+            {..Htmx::get(CALENDAR_ROUTE)
+                .target(CALENDAR_ID.clone())
+                .swap(SwapOption::OuterHtml)
+                .include(include_targets)
+                .push_url(switch_calendar_month.query_string(calendar_view))
+                .attrs()}
         >
             <div>
                 <Icon icon=button_icon/>
             </div>
-        </button>
+        </a>
     }
 }
 
-const WEEKDAYS: [&str; 7] = ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
 #[component]
-fn Weekdays() -> impl IntoView {
-    WEEKDAYS
-        .into_iter()
+fn Weekdays(
+    settings: CalendarSettings,
+    /// The rows start with their week number or week button
+    row_column: bool,
+) -> impl IntoView {
+    let locale = use_locale();
+
+    let week_number_header = row_column.then(|| {
+        let label = settings.show_week_numbers.then(|| t(Text::WeekNumberShort));
+        view! {
+            <div role="columnheader" class="text-sm text-faint font-bold">
+                {label}
+            </div>
+        }
+    });
+
+    let weekdays = std::iter::successors(Some(settings.week_start), |day| Some(day.next()))
+        .take(7)
         .map(|day| {
-            view! { <div class="text-gray-400 font-bold">{day}</div> }
+            view! {
+                <div
+                    role="columnheader"
+                    aria-label=locale.weekday_name(day)
+                    class="text-muted font-bold"
+                >
+                    {locale.weekday_short(day)}
+                </div>
+            }
         })
-        .collect_view()
+        .collect_view();
+
+    view! {
+        {week_number_header}
+        {weekdays}
+    }
 }
 
 // region:	  --- Utils
-#[derive(Debug, Serialize, Copy, Clone)]
+
+/// How the calendar of a plan is laid out for the viewer
+#[derive(Debug, Clone, Copy)]
+pub struct CalendarSettings {
+    pub week_start: Weekday,
+    pub show_week_numbers: bool,
+    /// Today in the viewer's zone, or in the plan's zone until the viewer's is known
+    pub today: Date,
+    /// Dates with this many users are marked as reaching the quorum
+    pub min_attendance: Option<usize>,
+    /// The plan is about the month of this date, for repeated plans
+    pub window_start: Option<Date>,
+    /// The date the owner settled on
+    pub final_date: Option<Date>,
+}
+
+impl CalendarSettings {
+    /// The plan's settings, with the locale's week start when the plan has none
+    pub fn new(plan: &plans::Model, context: &RequestContext) -> Self {
+        Self::at(plan, context, OffsetDateTime::now_utc())
+    }
+
+    fn at(plan: &plans::Model, context: &RequestContext, now: OffsetDateTime) -> Self {
+        let time_zone = context.time_zone.as_ref().unwrap_or(&plan.time_zone);
+
+        Self {
+            week_start: plan
+                .week_start
+                .map(Weekday::from)
+                .unwrap_or(context.locale.week_start()),
+            show_week_numbers: plan.show_week_numbers,
+            today: now.to_timezone(time_zone.tz()).date(),
+            min_attendance: quorum::min_attendance(plan),
+            window_start: plan.window_start,
+            final_date: plan.final_date,
+        }
+    }
+
+    /// Dates from today on, within the month of the window when the plan has one
+    pub fn in_window(&self, date: Date) -> bool {
+        let in_window_month = self.window_start.is_none_or(|window_start| {
+            (window_start.year(), window_start.month()) == (date.year(), date.month())
+        });
+
+        date >= self.today && in_window_month
+    }
+}
+
+#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq)]
 pub struct CalendarMonth {
     month: Month,
     year: i32,
 }
 
 impl CalendarMonth {
-    pub fn current_month() -> Self {
-        let now = OffsetDateTime::now_utc();
+    pub fn new(month: Month, year: i32) -> Self {
+        Self { month, year }
+    }
 
+    pub fn current_month(today: Date) -> Self {
         Self {
-            month: now.month(),
-            year: now.year(),
+            month: today.month(),
+            year: today.year(),
         }
     }
 
-    // This adds the dates of the previous and next months until
-    // the first day is a monday and the last day a sunday
-    // for fitting on the calendar
-    fn dates(&self) -> Vec<Date> {
-        let dates: Vec<Date> = (1..32)
+    /// The month asked for in the url, when both parts are there and the year has dates
+    pub fn from_query(month: Option<Month>, year: Option<i32>) -> Option<Self> {
+        let year = year.filter(|year| (Date::MIN.year()..=Date::MAX.year()).contains(year))?;
+
+        Some(Self::new(month?, year))
+    }
+
+    /// The month a plan opens on: the month of its final date once settled, else of the earliest
+    /// date anyone picked from today on, or the start of its window when nothing is picked yet,
+    /// and otherwise the current month
+    pub fn initial(users_with_dates: &[UserWithDates], settings: &CalendarSettings) -> Self {
+        if let Some(final_date) = settings.final_date {
+            return Self::current_month(final_date);
+        }
+        let today = settings.today;
+        let earliest_candidate = users_with_dates
+            .iter()
+            .flat_map(|(_, dates)| dates.iter().map(|date_model| date_model.date))
+            .filter(|date| *date >= today)
+            .min();
+        let window_start = settings.window_start.filter(|date| *date >= today);
+
+        Self::current_month(earliest_candidate.or(window_start).unwrap_or(today))
+    }
+
+    /// Query string of the plan page showing this month in `view`
+    pub fn query_string(&self, view: CalendarView) -> String {
+        match view {
+            CalendarView::Month => format!("?month={}&year={}", self.month, self.year),
+            _ => format!("?month={}&year={}&view={view}", self.month, self.year),
+        }
+    }
+
+    /// Query of the plan page on this month, for links that work without htmx
+    pub fn page_query(&self, view: CalendarView, user_public_id: Option<&PublicId>) -> String {
+        match user_public_id {
+            Some(user_public_id) => format!("{}&user={user_public_id}", self.query_string(view)),
+            None => self.query_string(view),
+        }
+    }
+
+    /// The days of the month itself, without padding
+    fn month_dates(&self) -> Vec<Date> {
+        (1..32)
             .map(|day| Date::from_calendar_date(self.year, self.month, day))
             .take_while(|date_result| date_result.is_ok())
             .filter_map(|date| date.ok()) // Basically just unwraps by throwing away errors, but errors have already been removed by the take_while
-            .collect();
+            .collect()
+    }
+
+    // This adds the dates of the previous and next months until
+    // the first day is the `week_start` and the last day the day before it
+    // for fitting on the calendar
+    // Months past the last representable year, like the one after December 9999, have none
+    fn dates(&self, week_start: Weekday) -> Vec<Date> {
+        let dates = self.month_dates();
+        let (Some(&first), Some(&last)) = (dates.first(), dates.last()) else {
+            return vec![];
+        };
 
         let mut padded = vec![];
 
-        // Pad dates backwards until the first day is Monday
-        let mut first = *dates.first().unwrap();
-        while first.weekday() != Weekday::Monday {
+        // Pad dates backwards until the first day is the start of the week
+        let mut first = first;
+        while first.weekday() != week_start {
             if let Some(previous_day) = first.previous_day() {
                 first = previous_day;
                 padded.insert(0, first);
@@ -439,9 +1460,9 @@ impl CalendarMonth {
         // Append the existing dates to the left padding
         padded.extend_from_slice(&dates);
 
-        // Pad the dates forward until the last day is Sunday
-        let mut last = *dates.last().unwrap();
-        while last.weekday() != Weekday::Sunday {
+        // Pad the dates forward until the last day is the end of the week
+        let mut last = last;
+        while last.weekday() != week_start.previous() {
             if let Some(next_day) = last.next_day() {
                 last = next_day;
                 padded.push(last);
@@ -454,6 +1475,51 @@ impl CalendarMonth {
     }
 }
 
+/// Layouts of the calendar, the multi-month ones start at the shown month
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Display)]
+pub enum CalendarView {
+    #[default]
+    #[serde(rename = "month")]
+    #[display("month")]
+    Month,
+    #[serde(rename = "2-months")]
+    #[display("2-months")]
+    TwoMonths,
+    #[serde(rename = "3-months")]
+    #[display("3-months")]
+    ThreeMonths,
+    /// Only the picked dates, as an agenda
+    #[serde(rename = "list")]
+    #[display("list")]
+    List,
+}
+
+impl CalendarView {
+    pub const ALL: [CalendarView; 4] = [
+        CalendarView::Month,
+        CalendarView::TwoMonths,
+        CalendarView::ThreeMonths,
+        CalendarView::List,
+    ];
+
+    fn month_count(self) -> usize {
+        match self {
+            CalendarView::Month | CalendarView::List => 1,
+            CalendarView::TwoMonths => 2,
+            CalendarView::ThreeMonths => 3,
+        }
+    }
+
+    fn text(self) -> Text {
+        match self {
+            CalendarView::Month => Text::ViewMonth,
+            CalendarView::TwoMonths => Text::ViewTwoMonths,
+            CalendarView::ThreeMonths => Text::ViewThreeMonths,
+            CalendarView::List => Text::ViewList,
+        }
+    }
+}
+
 fn previous_month(calendar_month: CalendarMonth) -> CalendarMonth {
     if calendar_month.month == Month::January {
         CalendarMonth {
@@ -482,7 +1548,15 @@ fn next_month(calendar_month: CalendarMonth) -> CalendarMonth {
     }
 }
 
-fn deserialize_month<'de, D>(deserializer: D) -> std::result::Result<Month, D::Error>
+/// ISO week number of a calendar row, which is the week its Monday is in
+fn iso_week(week: &[Date]) -> u8 {
+    week.iter()
+        .find(|date| date.weekday() == Weekday::Monday)
+        .or(week.first())
+        .map_or(0, |date| date.iso_week())
+}
+
+pub(super) fn deserialize_month<'de, D>(deserializer: D) -> std::result::Result<Month, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -504,43 +1578,279 @@ where
     }
 }
 
-fn is_today(date: Date) -> bool {
-    let today = time::OffsetDateTime::now_utc().date();
-    date == today
+pub(super) fn deserialize_month_option<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Month>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)?.as_deref() {
+        None | Some("") => Ok(None),
+        Some(s) => deserialize_month(s.into_deserializer()).map(Some),
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum DateRank {
-    All,
-    Some,
-    None,
-}
+// endregion: --- Utils
 
-pub fn ranked_dates(users_with_dates: &Vec<UserWithDates>) -> HashMap<Date, DateRank> {
-    let total_users = users_with_dates.len();
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use entity::types::TimeZoneName;
+    use time::macros::datetime;
+
+    use crate::test_support::{fx_context, fx_plan, fx_user_with_dates};
+
+    fn fx_settings(today: Date, window_start: Option<Date>) -> CalendarSettings {
+        CalendarSettings {
+            week_start: Weekday::Monday,
+            show_week_numbers: false,
+            today,
+            min_attendance: None,
+            window_start,
+            final_date: None,
+        }
+    }
 
-    // Flatten dates and count
-    let mut date_counts: HashMap<Date, usize> = HashMap::new();
+    #[test]
+    fn test_calendar_settings_today_in_viewer_zone() -> Result<()> {
+        // Already Tuesday in Amsterdam, still Monday in UTC and New York
+        let fx_now = datetime!(2026-10-19 22:30 UTC);
+        let mut fx_plan = fx_plan("Dinner")?;
+        let mut fx_context = fx_context();
+        let monday = Date::from_calendar_date(2026, Month::October, 19)?;
+        let tuesday = Date::from_calendar_date(2026, Month::October, 20)?;
+
+        // -- The plan's zone until the viewer's is known
+        assert_eq!(
+            CalendarSettings::at(&fx_plan, &fx_context, fx_now).today,
+            monday
+        );
+        fx_plan.time_zone = TimeZoneName::new("Europe/Amsterdam")?;
+        assert_eq!(
+            CalendarSettings::at(&fx_plan, &fx_context, fx_now).today,
+            tuesday
+        );
+
+        // -- The viewer's zone wins over the plan's
+        fx_context.time_zone = Some(TimeZoneName::new("America/New_York")?);
+        assert_eq!(
+            CalendarSettings::at(&fx_plan, &fx_context, fx_now).today,
+            monday
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calendar_month_initial() -> Result<()> {
+        let fx_today = Date::from_calendar_date(2026, Month::October, 19)?;
+        let fx_past = Date::from_calendar_date(2026, Month::September, 30)?;
+        let fx_december = Date::from_calendar_date(2026, Month::December, 24)?;
+        let fx_november = Date::from_calendar_date(2026, Month::November, 2)?;
+        let fx_window = Date::from_calendar_date(2027, Month::January, 1)?;
+
+        // -- Nothing picked, the current month
+        let settings = fx_settings(fx_today, None);
+        assert_eq!(
+            CalendarMonth::initial(&[fx_user_with_dates(1, "ann", &[fx_past])?], &settings),
+            CalendarMonth::new(Month::October, 2026)
+        );
+
+        // -- The earliest date from today on, past dates don't count
+        let fx_users = vec![
+            fx_user_with_dates(1, "ann", &[fx_past, fx_december])?,
+            fx_user_with_dates(2, "bob", &[fx_november])?,
+        ];
+        assert_eq!(
+            CalendarMonth::initial(&fx_users, &settings),
+            CalendarMonth::new(Month::November, 2026)
+        );
+
+        // -- The window of a repeated plan when nothing is picked yet
+        let settings = fx_settings(fx_today, Some(fx_window));
+        assert_eq!(
+            CalendarMonth::initial(&[], &settings),
+            CalendarMonth::new(Month::January, 2027)
+        );
+        assert_eq!(
+            CalendarMonth::initial(&fx_users, &settings),
+            CalendarMonth::new(Month::November, 2026)
+        );
+
+        // -- The final date once settled, even over earlier picks
+        let settings = CalendarSettings {
+            final_date: Some(fx_december),
+            ..fx_settings(fx_today, None)
+        };
+        assert_eq!(
+            CalendarMonth::initial(&fx_users, &settings),
+            CalendarMonth::new(Month::December, 2026)
+        );
 
-    for (_, dates) in users_with_dates {
-        for date in dates {
-            *date_counts.entry(date.date).or_insert(0) += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn test_calendar_month_query_round_trip() -> Result<()> {
+        let fx_user = PublicId::default();
+
+        for view in [
+            CalendarView::Month,
+            CalendarView::TwoMonths,
+            CalendarView::List,
+        ] {
+            let calendar_month = CalendarMonth::new(Month::February, 2027);
+            let page_query = calendar_month.page_query(view, Some(&fx_user));
+
+            // Read back the way the plan page reads its url
+            let plan_get: crate::plan_page::PlanGet =
+                serde_urlencoded::from_str(page_query.trim_start_matches('?'))?;
+
+            assert_eq!(
+                CalendarMonth::from_query(plan_get.month, plan_get.year),
+                Some(calendar_month)
+            );
+            assert_eq!(plan_get.view, view);
+            assert_eq!(plan_get.user, Some(fx_user.clone()));
         }
+
+        Ok(())
     }
 
-    // Step 2: Create a HashMap<Date, DateRank> to categorize each date
-    let mut date_rankings: HashMap<Date, DateRank> = HashMap::new();
+    #[test]
+    fn test_calendar_month_dates_week_start() -> Result<()> {
+        // October 2024 starts on a Tuesday and ends on a Thursday
+        let calendar_month = CalendarMonth::new(Month::October, 2024);
 
-    for (date, count) in date_counts {
-        let rank = if count == total_users {
-            DateRank::All
-        } else {
-            DateRank::Some
-        };
+        for week_start in [Weekday::Monday, Weekday::Saturday, Weekday::Sunday] {
+            let dates = calendar_month.dates(week_start);
+
+            assert_eq!(dates.len() % 7, 0);
+            assert_eq!(dates.first().map(|date| date.weekday()), Some(week_start));
+            assert_eq!(
+                dates.last().map(|date| date.weekday()),
+                Some(week_start.previous())
+            );
+        }
+
+        let sunday_first = calendar_month.dates(Weekday::Sunday);
+        assert_eq!(
+            sunday_first[0],
+            Date::from_calendar_date(2024, Month::September, 29)?
+        );
+
+        Ok(())
+    }
 
-        date_rankings.insert(date, rank);
+    #[test]
+    fn test_calendar_month_out_of_range_year() -> Result<()> {
+        assert_eq!(
+            CalendarMonth::from_query(Some(Month::January), Some(10000)),
+            None
+        );
+        assert_eq!(
+            CalendarMonth::from_query(Some(Month::December), Some(9999)),
+            Some(CalendarMonth::new(Month::December, 9999))
+        );
+
+        // The multi-month views still move on from the last month there is
+        let after_last = next_month(CalendarMonth::new(Month::December, 9999));
+        assert!(after_last.dates(Weekday::Monday).is_empty());
+
+        Ok(())
     }
 
-    date_rankings
+    #[test]
+    fn test_iso_week_of_row() -> Result<()> {
+        // The Sunday before a Monday still belongs to the row of that Monday's week
+        let calendar_month = CalendarMonth::new(Month::January, 2025);
+        let dates = calendar_month.dates(Weekday::Sunday);
+
+        let weeks: Vec<u8> = dates.chunks(7).map(iso_week).collect();
+
+        assert_eq!(weeks, vec![1, 2, 3, 4, 5]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dates_in_range_either_order() -> Result<()> {
+        // Friday 30 October to Tuesday 3 November 2026
+        let fx_from = Date::from_calendar_date(2026, Month::November, 3)?;
+        let fx_to = Date::from_calendar_date(2026, Month::October, 30)?;
+
+        assert_eq!(dates_in_range(fx_from, fx_to, DayFilter::All).len(), 5);
+        assert_eq!(
+            dates_in_range(fx_from, fx_to, DayFilter::Weekends),
+            vec![
+                Date::from_calendar_date(2026, Month::October, 31)?,
+                Date::from_calendar_date(2026, Month::November, 1)?
+            ]
+        );
+        assert_eq!(dates_in_range(fx_from, fx_to, DayFilter::Weekdays).len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_window_from_today_within_month() -> Result<()> {
+        let mut settings = fx_settings(Date::from_calendar_date(2026, Month::October, 19)?, None);
+
+        assert!(!settings.in_window(Date::from_calendar_date(2026, Month::October, 18)?));
+        assert!(settings.in_window(Date::from_calendar_date(2027, Month::March, 1)?));
+
+        // -- A repeated plan is only about its month
+        settings.window_start = Some(Date::from_calendar_date(2026, Month::November, 1)?);
+        assert!(settings.in_window(Date::from_calendar_date(2026, Month::November, 30)?));
+        assert!(!settings.in_window(Date::from_calendar_date(2026, Month::December, 1)?));
+        assert!(!settings.in_window(Date::from_calendar_date(2027, Month::November, 2)?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_label_with_availability() -> Result<()> {
+        let fx_date = Date::from_calendar_date(2026, Month::November, 3)?;
+        let fx_participant = |name: &str, required| crate::plan_page::ranking::Participant {
+            name: name.to_string(),
+            required,
+        };
+        let mut fx_availability = DateAvailability {
+            available: vec![fx_participant("Ann", false), fx_participant("Bob", false)],
+            unavailable: vec![fx_participant("Carl", false)],
+            maybe: vec![],
+        };
+
+        assert_eq!(
+            date_label(fx_date, &fx_availability, Locale::En),
+            "Tuesday 3 November 2026, Available: 2/3"
+        );
+
+        // -- Nobody there yet, only the date
+        assert_eq!(
+            date_label(
+                fx_date,
+                &DateAvailability {
+                    available: vec![],
+                    unavailable: vec![],
+                    maybe: vec![]
+                },
+                Locale::En
+            ),
+            "Tuesday 3 November 2026"
+        );
+
+        // -- A required user is missing
+        fx_availability.unavailable[0].required = true;
+        assert_eq!(
+            date_label(fx_date, &fx_availability, Locale::En),
+            "Tuesday 3 November 2026, Available: 2/3, Missing required"
+        );
+
+        Ok(())
+    }
 }
-// endregion: --- Utils
+// endregion: --- Tests
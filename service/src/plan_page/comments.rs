@@ -0,0 +1,293 @@
+//! The comment thread below the users, comments on a date also show up in the calendar
+
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::{Html, IntoResponse, Response},
+    routing::post,
+    Form, Router,
+};
+use axum_htmx::SwapOption;
+use entity::{
+    comments,
+    db::ModelManager,
+    plans,
+    types::{deserialize_public_id_option, CommentText, PublicId},
+    users,
+};
+use leptos::prelude::*;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use time::{Date, Month};
+use tracing::debug;
+
+use crate::{
+    error::Result,
+    htmx_helpers::{Htmx, HtmxId, HtmxInclude, HtmxRoute, HtmxSwapOob},
+    i18n::{t, use_locale, Text},
+    middleware::mw_rate_limit::{mw_rate_limit, RateLimiter},
+    plan_page::{
+        calendar::{
+            deserialize_month_option, Calendar, CalendarMonth, CalendarSettings, CalendarView,
+        },
+        filter_users_with_dates,
+        final_date::deserialize_date_option,
+        htmx_ids,
+    },
+    request_context::RequestContext,
+};
+
+static COMMENTS_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("comments"));
+
+pub type CommentWithAuthor = (comments::Model, Option<users::Model>);
+
+/// The notes on each date, with the name of their author in front
+pub type DateNotes = BTreeMap<Date, Vec<String>>;
+
+pub const COMMENTS_ROUTE: HtmxRoute = HtmxRoute::plan("/comments");
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route(
+            COMMENTS_ROUTE.path(),
+            post(create_comment_handler)
+                .layer(from_fn_with_state(RateLimiter::creation(), mw_rate_limit)),
+        )
+        .with_state(mm)
+}
+
+// region:	  --- Comment handlers
+
+#[derive(Debug, Deserialize)]
+struct CommentPost {
+    text: CommentText,
+    /// Empty for a comment on the plan as a whole
+    #[serde(default, deserialize_with = "deserialize_date_option")]
+    date: Option<Date>,
+    /// The author, anonymous without a current user
+    #[serde(default, deserialize_with = "deserialize_public_id_option")]
+    user_public_id: Option<PublicId>,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+async fn create_comment_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    context: RequestContext,
+    Form(comment_post): Form<CommentPost>,
+) -> Result<Response> {
+    debug!("{:<12} - create_comment - {plan_public_id}", "HANDLER");
+
+    comments::helpers::create_comment(
+        plan_public_id.clone(),
+        comment_post.user_public_id.clone(),
+        comment_post.date,
+        comment_post.text,
+        mm.clone(),
+    )
+    .await?;
+
+    // -- Re-render the thread, and the calendar for a new note on a date
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let comments =
+        comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+    let current_user_with_dates = comment_post
+        .user_public_id
+        .and_then(|user_public_id| filter_users_with_dates(&users_with_dates, user_public_id));
+    let settings = CalendarSettings::new(&plan, &context);
+    let calendar_month = CalendarMonth::from_query(comment_post.month, comment_post.year)
+        .unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &settings));
+
+    let view = context.render(|| {
+        view! {
+            <Comments comments=comments.clone()/>
+            <Calendar
+                users_with_dates=users_with_dates
+                current_user_with_dates=current_user_with_dates
+                calendar_month=calendar_month
+                calendar_view=comment_post.view
+                settings=settings
+                date_notes=date_notes(&comments)
+                swap_oob=HtmxSwapOob::OuterHtml
+            />
+        }
+    });
+    Ok(Html(view).into_response())
+}
+
+// endregion: --- Comment handlers
+
+/// The comments on a date, in the order they were written
+pub fn date_notes(comments: &[CommentWithAuthor]) -> DateNotes {
+    let mut notes = DateNotes::new();
+
+    for (comment, author) in comments {
+        if let Some(date) = comment.date {
+            notes
+                .entry(date)
+                .or_default()
+                .push(note(comment, author.as_ref()));
+        }
+    }
+
+    notes
+}
+
+fn note(comment: &comments::Model, author: Option<&users::Model>) -> String {
+    match author {
+        Some(author) => format!("{}: {}", author.name, comment.text),
+        None => comment.text.to_string(),
+    }
+}
+
+#[component]
+pub fn Comments(comments: Vec<CommentWithAuthor>) -> impl IntoView {
+    let locale = use_locale();
+
+    let rows = comments
+        .into_iter()
+        .map(|(comment, author)| {
+            let author = author
+                .map(|author| author.name.to_string())
+                .unwrap_or_else(|| locale.text(Text::Anonymous).to_string());
+            let date = comment.date.map(|date| {
+                format!(
+                    "{} {} {}",
+                    locale.weekday_name(date.weekday()),
+                    date.day(),
+                    locale.month_name(date.month())
+                )
+            });
+
+            view! {
+                <li class="border-b border-line py-2">
+                    <div class="flex justify-between text-xs text-muted">
+                        <span>{author}</span>
+                        <span>{date}</span>
+                    </div>
+                    <p class="whitespace-pre-line text-sm text-content">{comment.text.to_string()}</p>
+                </li>
+            }
+        })
+        .collect_view();
+    let include_targets = HtmxInclude::from(vec![
+        htmx_ids::USER_PUBLIC_ID.clone(),
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::CALENDAR_VIEW.clone(),
+    ]);
+
+    view! {
+        <div id=COMMENTS_ID.to_string() class="mx-auto max-w-80 my-8 text-muted">
+            <p class="text-sm">{t(Text::Comments)}</p>
+            <ul class="mt-2">{rows}</ul>
+            <form
+                {..Htmx::post(COMMENTS_ROUTE)
+                    .target(COMMENTS_ID.clone())
+                    .swap(SwapOption::OuterHtml)
+                    .include(include_targets)
+                    .attrs()}
+                class="mt-4 space-y-2"
+            >
+                <textarea
+                    name="text"
+                    required
+                    maxlength="500"
+                    placeholder=t(Text::CommentPlaceholder)
+                    class="w-full rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                ></textarea>
+                <div class="flex items-center justify-between space-x-2">
+                    <input
+                        type="date"
+                        name="date"
+                        title=t(Text::CommentDateHint)
+                        class="rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                    />
+                    <button
+                        type="submit"
+                        class="rounded-lg bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
+                    >
+                        {t(Text::AddComment)}
+                    </button>
+                </div>
+            </form>
+        </div>
+    }
+}
+
+/// Shows the notes on a date while it is hovered or focused, the date needs the `group` class
+#[component]
+pub fn DateNotesPopover(notes: Vec<String>) -> impl IntoView {
+    (!notes.is_empty()).then(|| {
+        let items = notes
+            .into_iter()
+            .map(|note| view! { <li>{note}</li> })
+            .collect_view();
+
+        view! {
+            <span class="absolute right-1 top-1 z-10 h-1.5 w-1.5 rounded-full bg-note"></span>
+            <ul class="absolute left-1/2 top-full z-20 mt-1 hidden w-48 -translate-x-1/2 space-y-1 rounded-lg bg-popover p-2 text-left text-xs text-content group-hover:block group-focus-within:block">
+                {items}
+            </ul>
+        }
+    })
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use time::OffsetDateTime;
+
+    use crate::test_support::fx_user;
+
+    fn fx_comment(date: Option<Date>, text: &str) -> Result<comments::Model> {
+        Ok(comments::Model {
+            id: 0,
+            public_id: PublicId::default(),
+            plan_id: 0,
+            user_id: None,
+            date,
+            text: CommentText::new(text)?,
+            ctime: OffsetDateTime::now_utc(),
+        })
+    }
+
+    #[test]
+    fn test_date_notes_per_date_with_author() -> Result<()> {
+        let fx_date = Date::from_calendar_date(2026, Month::November, 12)?;
+        let fx_author = fx_user(1, "ann")?;
+        let fx_comments = vec![
+            (
+                fx_comment(Some(fx_date), "Only afternoons")?,
+                Some(fx_author),
+            ),
+            (fx_comment(None, "Who brings food?")?, None),
+            (fx_comment(Some(fx_date), "Same here")?, None),
+        ];
+
+        let notes = date_notes(&fx_comments);
+
+        assert_eq!(
+            notes.into_iter().collect::<Vec<_>>(),
+            vec![(
+                fx_date,
+                vec!["ann: Only afternoons".to_string(), "Same here".to_string()]
+            )]
+        );
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,89 @@
+//! The links in notification emails, proven by the email token of the user
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Router,
+};
+use entity::{db::ModelManager, plans, types::PublicId, users};
+use leptos::prelude::*;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::{
+    app::Page,
+    error::{Error, Result},
+    i18n::{t, Text},
+    request_context::RequestContext,
+};
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route("/email/confirm", get(confirm_email_handler))
+        .route("/email/unsubscribe", get(unsubscribe_handler))
+        .with_state(mm)
+}
+
+#[derive(Debug, Deserialize)]
+struct EmailLinkGet {
+    user: PublicId,
+    token: String,
+}
+
+async fn confirm_email_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    Query(email_link): Query<EmailLinkGet>,
+) -> Result<Response> {
+    debug!("{:<12} - confirm_email - {plan_public_id}", "HANDLER");
+
+    require_user_of_plan(&plan_public_id, email_link.user.clone(), mm.clone()).await?;
+    users::helpers::confirm_email(email_link.user, &email_link.token, mm).await?;
+
+    Ok(Redirect::to(&format!("/plan/{plan_public_id}/")).into_response())
+}
+
+async fn unsubscribe_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    context: RequestContext,
+    Query(email_link): Query<EmailLinkGet>,
+) -> Result<Response> {
+    debug!("{:<12} - unsubscribe - {plan_public_id}", "HANDLER");
+
+    let plan = require_user_of_plan(&plan_public_id, email_link.user.clone(), mm.clone()).await?;
+    users::helpers::unsubscribe(email_link.user, &email_link.token, mm).await?;
+
+    let view = context.render(|| {
+        view! {
+            <Page title=plan.name.to_string()>
+                <h1 class="text-center">{plan.name.to_string()}</h1>
+                <p class="mx-auto max-w-80 my-8 text-center text-muted">
+                    {t(Text::Unsubscribed)} " "
+                    <a href=format!("/plan/{plan_public_id}/") class="underline hover:text-content">
+                        {t(Text::BackToPlan)}
+                    </a>
+                </p>
+            </Page>
+        }
+    });
+    Ok(Html(view).into_response())
+}
+
+/// The plan, as long as the user belongs to it
+async fn require_user_of_plan(
+    plan_public_id: &PublicId,
+    user_public_id: PublicId,
+    mm: ModelManager,
+) -> Result<plans::Model> {
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let user = users::helpers::user_by_public_id(user_public_id.clone(), mm).await?;
+    if user.plan_id != plan.id {
+        return Err(Error::Entity(entity::error::Error::EntityNotFound(
+            user_public_id.to_string(),
+        )));
+    }
+
+    Ok(plan)
+}
@@ -0,0 +1,270 @@
+//! The owner settles on the date of the plan, everyone can add it to their calendar
+
+use axum::{
+    extract::{Path, State},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use axum_extra::extract::CookieJar;
+use axum_htmx::SwapOption;
+use entity::{db::ModelManager, plans, types::PublicId};
+use http::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    StatusCode,
+};
+use leptos::prelude::*;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer};
+use time::{macros::format_description, Date, Duration, OffsetDateTime};
+use tracing::debug;
+
+use crate::{
+    config::web_config,
+    error::Result,
+    events::{self, PlanEvent},
+    htmx_helpers::{Htmx, HtmxId, HtmxRoute},
+    i18n::{t, use_locale, Text},
+    plan_page::owner::require_plan_owner,
+    request_context::RequestContext,
+};
+
+static FINAL_DATE_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("final_date"));
+
+pub const FINAL_ROUTE: HtmxRoute = HtmxRoute::plan("/final");
+pub const FINAL_ICS_ROUTE: HtmxRoute = HtmxRoute::plan("/final.ics");
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route(FINAL_ROUTE.path(), post(set_final_date_handler))
+        .route(FINAL_ICS_ROUTE.path(), get(final_date_ics_handler))
+        .with_state(mm)
+}
+
+// region:	  --- Final date handlers
+
+#[derive(Debug, Deserialize)]
+struct FinalDatePost {
+    /// Empty reopens the plan
+    #[serde(deserialize_with = "deserialize_date_option")]
+    final_date: Option<Date>,
+}
+
+async fn set_final_date_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(final_date_post): Form<FinalDatePost>,
+) -> Result<Response> {
+    debug!("{:<12} - set_final_date - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    let updated_plan =
+        plans::helpers::set_final_date(plan_public_id.clone(), final_date_post.final_date, mm)
+            .await?;
+    if let Some(date) = updated_plan
+        .final_date
+        .filter(|_| plan.final_date != updated_plan.final_date)
+    {
+        events::publish(PlanEvent::PlanFinalized {
+            plan_public_id,
+            date,
+        });
+    }
+
+    let view = context.render(|| view! { <FinalDate plan=updated_plan is_owner=true/> });
+    Ok(Html(view).into_response())
+}
+
+async fn final_date_ics_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+) -> Result<Response> {
+    debug!("{:<12} - final_date_ics - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id, mm).await?;
+    let Some(date) = plan.final_date else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    Ok((
+        [
+            (CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (CONTENT_DISPOSITION, r#"attachment; filename="plan.ics""#),
+        ],
+        final_date_ics(&plan, date, &web_config().PUBLIC_URL),
+    )
+        .into_response())
+}
+
+/// The empty input stands for no date
+pub(super) fn deserialize_date_option<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Date>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)?
+        .as_deref()
+        .map(str::trim)
+    {
+        None | Some("") => Ok(None),
+        Some(s) => Date::parse(s, format_description!("[year]-[month]-[day]"))
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+// endregion: --- Final date handlers
+
+// region:	  --- Calendar file
+
+/// All-day event on the final date, in the iCalendar format
+pub fn final_date_ics(plan: &plans::Model, date: Date, public_url: &str) -> String {
+    let day = format_description!("[year][month][day]");
+    let now = OffsetDateTime::now_utc();
+    let lines = [
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//picktheday//EN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@picktheday", plan.public_id),
+        format!(
+            "DTSTAMP:{}",
+            now.format(format_description!(
+                "[year][month][day]T[hour][minute][second]Z"
+            ))
+            .unwrap_or_default()
+        ),
+        format!(
+            "DTSTART;VALUE=DATE:{}",
+            date.format(day).unwrap_or_default()
+        ),
+        // The end of all-day events is exclusive
+        format!(
+            "DTEND;VALUE=DATE:{}",
+            (date + Duration::days(1)).format(day).unwrap_or_default()
+        ),
+        format!("SUMMARY:{}", escape_ics_text(&plan.name.to_string())),
+        format!("URL:{public_url}/plan/{}/", plan.public_id),
+        "END:VEVENT".to_string(),
+        "END:VCALENDAR".to_string(),
+    ];
+
+    lines
+        .iter()
+        .map(|line| fold_ics_line(line))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// Lines longer than 75 bytes continue on the next line after a space, never splitting a character
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_len = 0;
+    for c in line.chars() {
+        if line_len + c.len_utf8() > 75 {
+            folded += "\r\n ";
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += c.len_utf8();
+    }
+
+    folded + "\r\n"
+}
+
+// endregion: --- Calendar file
+
+/// The final date for everyone, and the form to set it for the owner
+#[component]
+pub fn FinalDate(plan: plans::Model, is_owner: bool) -> impl IntoView {
+    let locale = use_locale();
+
+    let announcement = plan.final_date.map(|date| {
+        view! {
+            <p class="text-content font-bold">
+                {t(Text::FinalDate)} ": " {locale.weekday_name(date.weekday())} " " {date.day()}
+                " " {locale.month_name(date.month())} " " {date.year()}
+            </p>
+            <a href=FINAL_ICS_ROUTE.to_string() download class="text-sm underline hover:text-content">
+                {t(Text::AddToCalendar)}
+            </a>
+        }
+    });
+    let form = is_owner.then(|| {
+        view! {
+            <form
+                {..Htmx::post(FINAL_ROUTE)
+                    .target(FINAL_DATE_ID.clone())
+                    .swap(SwapOption::OuterHtml)
+                    .attrs()}
+                class="mt-4 flex items-center justify-center space-x-2"
+            >
+                <input
+                    type="date"
+                    name="final_date"
+                    value=plan.final_date.map(|date| date.to_string())
+                    title=t(Text::FinalDateHint)
+                    class="rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                />
+                <button
+                    type="submit"
+                    class="rounded-lg bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
+                >
+                    {t(Text::Finalize)}
+                </button>
+            </form>
+        }
+    });
+
+    view! {
+        <div id=FINAL_DATE_ID.to_string() class="mx-auto max-w-80 my-8 text-center text-muted">
+            {announcement}
+            {form}
+        </div>
+    }
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use time::Month;
+
+    use crate::test_support::fx_plan;
+
+    #[test]
+    fn test_final_date_ics_all_day_event() -> Result<()> {
+        let mut fx_plan = fx_plan("Dinner; with friends, at Ann's")?;
+        fx_plan.public_id = PublicId::new("Ln64VOp4fBgSxctB")?;
+        let fx_date = Date::from_calendar_date(2026, Month::December, 31)?;
+
+        let ics = final_date_ics(&fx_plan, fx_date, "https://picktheday.example");
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261231\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20270101\r\n"));
+        assert!(ics.contains("SUMMARY:Dinner\\; with friends\\, at Ann's\r\n"));
+        assert!(ics.contains("URL:https://picktheday.example/plan/Ln64VOp4fBgSxctB/\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
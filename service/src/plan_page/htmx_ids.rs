@@ -1,12 +1,20 @@
+//! Defines htmx input ids that exist on the plan page which are required by non-related elements
+//! Elements that are children should just be passed ids of parents if necessary
+
 use crate::htmx_helpers::{HtmxId, HtmxInput};
 use once_cell::sync::Lazy;
 
-/// Defines htmx input ids that exist on the plan page which are required by non-related elements
-/// Elements that are children should just be passed ids of parents if necessary
-
 // region:	  --- Global htmx inputs
 pub static USER_PUBLIC_ID: Lazy<HtmxInput> =
     Lazy::new(|| HtmxInput::new(HtmxId::new("user_public_id"), "user_public_id"));
+/// The month the calendar currently shows
+pub static CALENDAR_MONTH: Lazy<HtmxInput> =
+    Lazy::new(|| HtmxInput::new(HtmxId::new("calendar_month"), "month"));
+pub static CALENDAR_YEAR: Lazy<HtmxInput> =
+    Lazy::new(|| HtmxInput::new(HtmxId::new("calendar_year"), "year"));
+/// The layout the calendar currently shows
+pub static CALENDAR_VIEW: Lazy<HtmxInput> =
+    Lazy::new(|| HtmxInput::new(HtmxId::new("calendar_view"), "view"));
 // endregion: --- Global htmx inputs
 
 // region:	  --- Global htmx IDs
@@ -1,51 +1,92 @@
 use crate::{
     app::Page,
     error::Result,
+    htmx_helpers::HtmxRoute,
+    http_cache::PlanVersion,
+    middleware::mw_rate_limit::{mw_rate_limit, RateLimiter},
+    request_context::RequestContext,
     util_components::{CopyToClipboard, Icon},
 };
 use axum::{
     body::Body,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
     response::{Html, IntoResponse, Redirect, Response},
     routing::{get, post},
     Form, Router,
 };
-use axum_htmx::HxRedirect;
-use calendar::{Calendar, CalendarMonth};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use axum_htmx::{HxRedirect, HxRequest};
+use calendar::{Calendar, CalendarMonth, CalendarSettings, CalendarView};
+use comments::{date_notes, CommentWithAuthor, Comments};
 use entity::{
-    dates,
+    comments as plan_comments, dates,
     db::ModelManager,
     plans::{self},
-    sea_orm::EntityTrait,
-    types::{PlanName, PublicId},
-    users,
+    poll_questions::{self, PollWithOptions},
+    types::{deserialize_public_id_option, PlanName, PublicId},
+    users, webhook_deliveries,
 };
-use http::{StatusCode, Uri};
+use final_date::FinalDate;
+use http::{HeaderMap, StatusCode, Uri};
 use leptos::prelude::*;
+use owner::OwnerLink;
+use polls::Polls;
+use repeat::RepeatPlan;
 use serde::Deserialize;
+use settings::PlanSettings;
+use time::Month;
 use tracing::debug;
 use user::Users;
+use webhooks::Webhooks;
 
+mod agenda;
 mod calendar;
+mod comments;
+mod email;
+pub mod final_date;
 mod htmx_ids;
+mod owner;
+mod polls;
+mod quorum;
+mod ranking;
+mod repeat;
 mod results;
+mod settings;
 mod user;
+mod webhooks;
+
+pub const PLAN_ROUTE: HtmxRoute = HtmxRoute::root("/plan");
 
 pub fn routes(mm: ModelManager) -> Router {
-    Router::new().nest(
-        "/plan",
-        Router::new()
-            .route("/", post(create_plan_handler))
-            .route("/:plan_slug", get(redirect_plan_handler))
-            .nest(
-                "/:plan_slug/",
-                Router::new()
-                    .route("/", get(plan_page_handler))
-                    .merge(calendar::routes(mm.clone()))
-                    .merge(user::routes(mm.clone())),
-            )
-            .with_state(mm),
-    )
+    Router::new()
+        .route(
+            PLAN_ROUTE.path(),
+            post(create_plan_handler)
+                .layer(from_fn_with_state(RateLimiter::creation(), mw_rate_limit)),
+        )
+        .nest(
+            PLAN_ROUTE.path(),
+            Router::new()
+                .route("/:plan_slug", get(redirect_plan_handler))
+                .nest(
+                    "/:plan_slug/",
+                    Router::new()
+                        .route("/", get(plan_page_handler))
+                        .merge(calendar::routes(mm.clone()))
+                        .merge(comments::routes(mm.clone()))
+                        .merge(email::routes(mm.clone()))
+                        .merge(final_date::routes(mm.clone()))
+                        .merge(owner::routes(mm.clone()))
+                        .merge(polls::routes(mm.clone()))
+                        .merge(repeat::routes(mm.clone()))
+                        .merge(settings::routes(mm.clone()))
+                        .merge(user::routes(mm.clone()))
+                        .merge(webhooks::routes(mm.clone())),
+                )
+                .with_state(mm.clone()),
+        )
+        .with_state(mm)
 }
 
 // region:	  --- Plan creation
@@ -57,20 +98,35 @@ struct PlanPost {
 #[derive(Debug)]
 struct CreatePlanResponse {
     plan_url: Uri,
+    /// Makes the creator the owner of the plan
+    owner_cookie: Option<Cookie<'static>>,
+    /// Plain form posts get a regular redirect instead of the `HX-Redirect` header
+    htmx: bool,
 }
 
 impl IntoResponse for CreatePlanResponse {
     fn into_response(self) -> Response {
+        let jar = self
+            .owner_cookie
+            .into_iter()
+            .fold(CookieJar::new(), CookieJar::add);
+
+        if !self.htmx {
+            return (jar, Redirect::to(&self.plan_url.to_string())).into_response();
+        }
+
         let status = StatusCode::CREATED;
         let redirect = HxRedirect::from(self.plan_url);
         let body = Body::empty();
 
-        (status, redirect, body).into_response()
+        (status, redirect, jar, body).into_response()
     }
 }
 
 async fn create_plan_handler(
     State(mm): State<ModelManager>,
+    HxRequest(htmx): HxRequest,
+    context: RequestContext,
     Form(plan_post): Form<PlanPost>,
 ) -> Result<CreatePlanResponse> {
     debug!(
@@ -78,38 +134,136 @@ async fn create_plan_handler(
         "HANDLER", plan_post.plan_name
     );
 
-    let new_plan = plans::helpers::create_plan(plan_post.plan_name, mm).await?;
+    // -- The plan starts out in the zone of its creator
+    let time_zone = context.time_zone.unwrap_or_default();
+    let new_plan = plans::helpers::create_plan(plan_post.plan_name, time_zone, mm).await?;
 
     let plan_url = format!("/plan/{}/", new_plan.public_id).parse::<Uri>()?;
 
     // Return an empty body with the HX-Redirect header
-    Ok(CreatePlanResponse { plan_url })
+    Ok(CreatePlanResponse {
+        plan_url,
+        owner_cookie: owner::owner_cookie(&new_plan),
+        htmx,
+    })
 }
 // endregion: --- Plan creation
 
 // region:	  --- Plan page
+#[derive(Debug, Deserialize)]
+struct PlanGet {
+    #[serde(default, deserialize_with = "calendar::deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+    /// The user editing their dates, so the page works without htmx
+    #[serde(default, deserialize_with = "deserialize_public_id_option")]
+    user: Option<PublicId>,
+}
+
 async fn plan_page_handler(
     State(mm): State<ModelManager>,
     Path(plan_public_id): Path<PublicId>,
-) -> Result<impl IntoResponse> {
+    uri: Uri,
+    headers: HeaderMap,
+    jar: CookieJar,
+    context: RequestContext,
+    Query(plan_get): Query<PlanGet>,
+) -> Result<Response> {
     debug!("{:<12} - plan_page_handler - {plan_public_id}", "HANDLER");
 
     // -- Get the plan
     let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
 
-    // -- Get the users with dates
+    // -- Only the owner manages webhooks, their deliveries don't change the plan
+    let is_owner = owner::is_plan_owner(&plan, &jar);
+    let webhooks_with_deliveries = match is_owner {
+        true => Some(webhooks::webhooks_with_deliveries(plan_public_id.clone(), mm.clone()).await?),
+        false => None,
+    };
+    let latest_delivery = webhooks_with_deliveries
+        .as_ref()
+        .and_then(|(_, deliveries)| deliveries.first())
+        .map(|delivery| delivery.id.to_string())
+        .unwrap_or_default();
+
+    // -- Nothing changed since the last visit
+    let calendar_settings = CalendarSettings::new(&plan, &context);
+    let version = PlanVersion::new(
+        &plan,
+        calendar_settings.today,
+        &[
+            &uri.to_string(),
+            &context.csrf_token.to_string(),
+            &context.locale.to_string(),
+            &context.theme.to_string(),
+            &is_owner.to_string(),
+            &latest_delivery,
+        ],
+    );
+    if version.is_fresh(&headers) {
+        return Ok(version.not_modified());
+    }
+
+    // -- Get the users with dates, the comments and the polls of the plan
+    let comments =
+        plan_comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
+    let polls =
+        poll_questions::helpers::polls_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
     let users_with_dates =
-        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await;
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+
+    // -- The month from the url, or the one with the first upcoming candidate date
+    let calendar_month = CalendarMonth::from_query(plan_get.month, plan_get.year)
+        .unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &calendar_settings));
 
-    let view = view! { <PlanPage plan=plan users_with_dates=users_with_dates.unwrap()/> }.to_html();
-    Ok(Html(view))
+    let view = context.render(|| {
+        view! {
+            <PlanPage
+                plan=plan
+                users_with_dates=users_with_dates
+                comments=comments
+                polls=polls
+                calendar_month=calendar_month
+                calendar_view=plan_get.view
+                calendar_settings=calendar_settings
+                current_user_public_id=plan_get.user
+                is_owner=is_owner
+                webhooks_with_deliveries=webhooks_with_deliveries
+            />
+        }
+    });
+    Ok((version, Html(view)).into_response())
 }
 
 #[component]
-fn PlanPage(plan: plans::Model, users_with_dates: Vec<UserWithDates>) -> impl IntoView {
+fn PlanPage(
+    plan: plans::Model,
+    users_with_dates: Vec<UserWithDates>,
+    comments: Vec<CommentWithAuthor>,
+    polls: Vec<PollWithOptions>,
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    calendar_settings: CalendarSettings,
+    current_user_public_id: Option<PublicId>,
+    is_owner: bool,
+    webhooks_with_deliveries: Option<(
+        Vec<entity::webhooks::Model>,
+        Vec<webhook_deliveries::Model>,
+    )>,
+) -> impl IntoView {
     let plan_title = plan.name.to_string();
 
-    let url = plan.public_id;
+    let url = plan.public_id.clone();
+    // Ids of users from other plans are ignored
+    let current_user_with_dates = current_user_public_id
+        .and_then(|public_id| filter_users_with_dates(&users_with_dates, public_id));
+    let current_user = current_user_with_dates
+        .as_ref()
+        .map(|(user, _)| user.public_id.clone());
 
     view! {
         <Page title=plan_title.clone()>
@@ -121,13 +275,52 @@ fn PlanPage(plan: plans::Model, users_with_dates: Vec<UserWithDates>) -> impl In
                     </CopyToClipboard>
                 </div>
             </div>
+            <FinalDate plan=plan.clone() is_owner=is_owner/>
 
             <Calendar
                 users_with_dates=users_with_dates.clone()
-                current_user_with_dates=None
-                calendar_month=CalendarMonth::current_month()
+                current_user_with_dates=current_user_with_dates
+                calendar_month=calendar_month
+                calendar_view=calendar_view
+                settings=calendar_settings
+                date_notes=date_notes(&comments)
+            />
+            <Polls
+                polls=polls
+                users_with_dates=users_with_dates.clone()
+                current_user=current_user.clone()
+                is_owner=is_owner
             />
-            <Users users_with_dates=users_with_dates current_user=None/>
+            <Users
+                users_with_dates=users_with_dates
+                current_user=current_user
+                calendar_month=calendar_month
+                calendar_view=calendar_view
+                is_owner=is_owner
+            />
+            <Comments comments=comments/>
+            {is_owner
+                .then(|| {
+                    view! {
+                        <PlanSettings
+                            week_start=plan.week_start
+                            show_week_numbers=plan.show_week_numbers
+                            time_zone=plan.time_zone.clone()
+                            min_attendance=plan.min_attendance
+                        />
+                    }
+                })}
+            {webhooks_with_deliveries
+                .map(|(webhooks, deliveries)| {
+                    view! { <Webhooks webhooks=webhooks deliveries=deliveries open=false/> }
+                })}
+            {is_owner
+                .then(|| {
+                    view! {
+                        <RepeatPlan/>
+                        <OwnerLink plan=plan/>
+                    }
+                })}
         </Page>
     }
 }
@@ -149,6 +342,21 @@ async fn redirect_plan_handler(Path(page_slug): Path<String>) -> impl IntoRespon
 
 pub type UserWithDates = (users::Model, Vec<dates::Model>);
 
+/// The plan page as the user left it, where plain form posts redirect to
+fn plan_page_redirect(
+    plan_public_id: &PublicId,
+    user_public_id: &PublicId,
+    calendar_month: Option<CalendarMonth>,
+    calendar_view: CalendarView,
+) -> Redirect {
+    let query = match calendar_month {
+        Some(calendar_month) => calendar_month.page_query(calendar_view, Some(user_public_id)),
+        None => format!("?user={user_public_id}"),
+    };
+
+    Redirect::to(&format!("/plan/{plan_public_id}/{query}"))
+}
+
 fn filter_users_with_dates(
     users_with_dates: &[UserWithDates],
     user_public_id: PublicId,
@@ -0,0 +1,113 @@
+//! The browser that created a plan owns it, proven by the plan's owner secret in a cookie.
+//!
+//! The owner link sets the cookie in another browser, e.g. to manage the plan from a phone.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Redirect},
+    routing::get,
+    Router,
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+use entity::{db::ModelManager, plans, types::PublicId};
+use leptos::prelude::*;
+use serde::Deserialize;
+use time::Duration;
+use tracing::debug;
+
+use crate::{
+    error::{Error, Result},
+    i18n::{t, Text},
+};
+
+const OWNER_COOKIE: &str = "plan_owner";
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route("/owner", get(claim_owner_handler))
+        .with_state(mm)
+}
+
+/// Cookie proving ownership, scoped to the pages of this plan only
+pub fn owner_cookie(plan: &plans::Model) -> Option<Cookie<'static>> {
+    let secret = plan.owner_secret.as_ref()?;
+
+    Some(
+        Cookie::build((OWNER_COOKIE, secret.expose().to_string()))
+            .path(format!("/plan/{}/", plan.public_id))
+            .http_only(true)
+            .max_age(Duration::days(365))
+            .same_site(SameSite::Lax)
+            .build(),
+    )
+}
+
+pub fn is_plan_owner(plan: &plans::Model, jar: &CookieJar) -> bool {
+    match (&plan.owner_secret, jar.get(OWNER_COOKIE)) {
+        (Some(secret), Some(cookie)) => secret.matches(cookie.value()),
+        _ => false,
+    }
+}
+
+/// Errors unless the request comes from the owner of the plan
+pub fn require_plan_owner(plan: &plans::Model, jar: &CookieJar) -> Result<()> {
+    if is_plan_owner(plan, jar) {
+        Ok(())
+    } else {
+        Err(Error::NotPlanOwner)
+    }
+}
+
+// region:	  --- Owner handler
+
+#[derive(Debug, Deserialize)]
+struct OwnerGet {
+    secret: String,
+}
+
+async fn claim_owner_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    Query(owner_get): Query<OwnerGet>,
+) -> Result<impl IntoResponse> {
+    debug!("{:<12} - claim_owner - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm).await?;
+    let cookie = plan
+        .owner_secret
+        .as_ref()
+        .filter(|secret| secret.matches(&owner_get.secret))
+        .and(owner_cookie(&plan))
+        .ok_or(Error::NotPlanOwner)?;
+
+    Ok((
+        jar.add(cookie),
+        Redirect::to(&format!("/plan/{plan_public_id}/")),
+    ))
+}
+
+// endregion: --- Owner handler
+
+/// Link that makes another browser an owner of the plan, only shown to owners
+#[component]
+pub fn OwnerLink(plan: plans::Model) -> impl IntoView {
+    let owner_url = plan
+        .owner_secret
+        .as_ref()
+        .map(|secret| format!("/plan/{}/owner?secret={}", plan.public_id, secret.expose()))
+        .unwrap_or_default();
+
+    view! {
+        <p class="mx-auto max-w-80 my-8 text-sm text-muted">
+            <a href=owner_url class="underline hover:text-content">
+                {t(Text::OwnerLink)}
+            </a>
+            " - "
+            {t(Text::OwnerLinkHint)}
+        </p>
+    }
+}
@@ -0,0 +1,474 @@
+//! Questions besides the date, like where to go. Users vote yes, maybe or no on each option
+//! and the options are ranked like the dates in the calendar.
+
+use axum::{
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
+    response::{Html, IntoResponse, Response},
+    routing::post,
+    Form, Router,
+};
+use axum_extra::extract::CookieJar;
+use axum_htmx::SwapOption;
+use entity::{
+    db::ModelManager,
+    plans,
+    poll_questions::{self, PollWithOptions, MAX_OPTIONS_PER_POLL},
+    poll_votes,
+    types::{deserialize_public_id_option, PollOptionText, PollQuestionText, PublicId, Vote},
+    users,
+};
+use leptos::prelude::*;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::debug;
+
+use crate::{
+    error::{Error, Result},
+    htmx_helpers::{Htmx, HtmxId, HtmxRoute, HtmxSwapOob},
+    i18n::{t, use_locale, Locale, Text},
+    middleware::mw_rate_limit::{mw_rate_limit, RateLimiter},
+    plan_page::{
+        htmx_ids,
+        owner::{is_plan_owner, require_plan_owner},
+        ranking::{DateAvailability, Participant},
+        UserWithDates,
+    },
+    request_context::RequestContext,
+    util_components::Icon,
+};
+
+pub static POLLS_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("polls"));
+
+pub const POLLS_ROUTE: HtmxRoute = HtmxRoute::plan("/polls");
+pub const VOTE_ROUTE: HtmxRoute = HtmxRoute::plan("/polls/vote");
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route(
+            POLLS_ROUTE.path(),
+            post(create_poll_handler)
+                .layer(from_fn_with_state(RateLimiter::creation(), mw_rate_limit))
+                .delete(delete_poll_handler),
+        )
+        .route(VOTE_ROUTE.path(), post(vote_handler))
+        .with_state(mm)
+}
+
+// region:	  --- Poll handlers
+
+#[derive(Debug, Deserialize)]
+struct PollPost {
+    question: PollQuestionText,
+    /// One option per line
+    options: String,
+    #[serde(default, deserialize_with = "deserialize_public_id_option")]
+    user_public_id: Option<PublicId>,
+}
+
+async fn create_poll_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(poll_post): Form<PollPost>,
+) -> Result<Response> {
+    debug!("{:<12} - create_poll - {plan_public_id}", "HANDLER");
+
+    let options = parse_options(&poll_post.options, context.locale)?;
+    poll_questions::helpers::create_poll(
+        plan_public_id.clone(),
+        poll_post.question,
+        options,
+        mm.clone(),
+    )
+    .await?;
+
+    render_polls(plan_public_id, poll_post.user_public_id, jar, context, mm).await
+}
+
+#[derive(Debug, Deserialize)]
+struct PollDelete {
+    poll: PublicId,
+    #[serde(default, deserialize_with = "deserialize_public_id_option")]
+    user_public_id: Option<PublicId>,
+}
+
+async fn delete_poll_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Query(poll_delete): Query<PollDelete>,
+) -> Result<Response> {
+    debug!("{:<12} - delete_poll - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    poll_questions::helpers::delete_poll(plan_public_id.clone(), poll_delete.poll, mm.clone())
+        .await?;
+
+    render_polls(plan_public_id, poll_delete.user_public_id, jar, context, mm).await
+}
+
+#[derive(Debug, Deserialize)]
+struct VotePost {
+    option: PublicId,
+    vote: Vote,
+    user_public_id: PublicId,
+}
+
+async fn vote_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(vote_post): Form<VotePost>,
+) -> Result<Response> {
+    debug!(
+        "{:<12} - vote - {plan_public_id} - {}",
+        "HANDLER", vote_post.vote
+    );
+
+    poll_votes::helpers::set_vote(
+        plan_public_id.clone(),
+        vote_post.user_public_id.clone(),
+        vote_post.option,
+        vote_post.vote,
+        mm.clone(),
+    )
+    .await?;
+
+    render_polls(
+        plan_public_id,
+        Some(vote_post.user_public_id),
+        jar,
+        context,
+        mm,
+    )
+    .await
+}
+
+async fn render_polls(
+    plan_public_id: PublicId,
+    current_user: Option<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    mm: ModelManager,
+) -> Result<Response> {
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let polls =
+        poll_questions::helpers::polls_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+
+    let view = context.render(|| {
+        view! {
+            <Polls
+                polls=polls
+                users_with_dates=users_with_dates
+                current_user=current_user
+                is_owner=is_plan_owner(&plan, &jar)
+            />
+        }
+    });
+    Ok(Html(view).into_response())
+}
+
+/// The non-empty lines, a poll needs at least two options
+fn parse_options(options: &str, locale: Locale) -> Result<Vec<PollOptionText>> {
+    let options = options
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            PollOptionText::new(line)
+                .map_err(|err| Error::PollInvalid(format!("{line:.40}: {err}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match options.len() {
+        0 | 1 => Err(Error::PollInvalid(
+            locale.text(Text::PollTooFewOptions).to_string(),
+        )),
+        count if count > MAX_OPTIONS_PER_POLL => Err(Error::PollInvalid(format!(
+            "{} {MAX_OPTIONS_PER_POLL}",
+            locale.text(Text::PollTooManyOptions)
+        ))),
+        _ => Ok(options),
+    }
+}
+
+// endregion: --- Poll handlers
+
+/// Who voted what on an option, users without a vote count as a no
+pub fn option_availability(
+    votes: &[poll_votes::Model],
+    users_with_dates: &[UserWithDates],
+) -> DateAvailability {
+    let mut availability = DateAvailability {
+        available: vec![],
+        unavailable: vec![],
+        maybe: vec![],
+    };
+
+    for (user, _) in users_with_dates {
+        let vote = votes.iter().find(|vote| vote.user_id == user.id);
+        let participants = match vote.map(|vote| vote.vote) {
+            Some(Vote::Yes) => &mut availability.available,
+            Some(Vote::Maybe) => &mut availability.maybe,
+            Some(Vote::No) | None => &mut availability.unavailable,
+        };
+        participants.push(Participant::from(user));
+    }
+
+    availability
+}
+
+#[component]
+pub fn Polls(
+    polls: Vec<PollWithOptions>,
+    users_with_dates: Vec<UserWithDates>,
+    /// Only the current user can vote
+    current_user: Option<PublicId>,
+    /// Owners can delete polls
+    is_owner: bool,
+    /// Set when the polls come along with the response of another component
+    #[prop(optional)]
+    swap_oob: HtmxSwapOob,
+) -> impl IntoView {
+    let current_user_id = current_user.as_ref().and_then(|current_user| {
+        users_with_dates
+            .iter()
+            .find(|(user, _)| &user.public_id == current_user)
+            .map(|(user, _)| user.id)
+    });
+
+    let questions = polls
+        .into_iter()
+        .map(|(question, options)| {
+            let delete_button = is_owner.then(|| {
+                view! {
+                    <button
+                        type="button"
+                        title=t(Text::DeletePoll)
+                        {..polls_request(Htmx::delete(POLLS_ROUTE))
+                            .vals(json!({ "poll": question.public_id.to_string() }))
+                            .attrs()}
+                    >
+                        <Icon icon=Icon::Delete/>
+                    </button>
+                }
+            });
+            let option_rows = options
+                .into_iter()
+                .map(|(option, votes)| {
+                    let own_vote = current_user_id.and_then(|user_id| {
+                        votes
+                            .iter()
+                            .find(|vote| vote.user_id == user_id)
+                            .map(|vote| vote.vote)
+                    });
+                    let availability = option_availability(&votes, &users_with_dates);
+                    view! {
+                        <PollOption
+                            option=option
+                            availability=availability
+                            own_vote=own_vote
+                            can_vote=current_user_id.is_some()
+                        />
+                    }
+                })
+                .collect_view();
+
+            view! {
+                <li class="mt-4">
+                    <div class="flex items-center justify-between">
+                        <span class="text-content font-bold">{question.text.to_string()}</span>
+                        {delete_button}
+                    </div>
+                    <ul>{option_rows}</ul>
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div
+            id=POLLS_ID.to_string()
+            class="mx-auto max-w-md my-8 text-left text-muted"
+            hx-swap-oob=swap_oob.attribute()
+        >
+            <ul>{questions}</ul>
+            <details class="mt-4 text-sm">
+                <summary>{t(Text::AddPoll)}</summary>
+                <form
+                    {..polls_request(Htmx::post(POLLS_ROUTE)).attrs()}
+                    class="mt-2 space-y-2"
+                >
+                    <input
+                        type="text"
+                        name="question"
+                        required
+                        maxlength="256"
+                        placeholder=t(Text::PollQuestionPlaceholder)
+                        class="w-full rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                    />
+                    <textarea
+                        name="options"
+                        required
+                        placeholder=t(Text::PollOptionsPlaceholder)
+                        class="w-full rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                    ></textarea>
+                    <button
+                        type="submit"
+                        class="w-full rounded-lg bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
+                    >
+                        {t(Text::AddPoll)}
+                    </button>
+                </form>
+            </details>
+        </div>
+    }
+}
+
+/// Poll requests say who is asking, and re-render all polls
+fn polls_request(htmx: Htmx) -> Htmx {
+    htmx.include(vec![htmx_ids::USER_PUBLIC_ID.clone()])
+        .target(POLLS_ID.clone())
+        .swap(SwapOption::OuterHtml)
+}
+
+/// An option ranked like a date, with the vote buttons for the current user
+#[component]
+fn PollOption(
+    option: entity::poll_options::Model,
+    availability: DateAvailability,
+    own_vote: Option<Vote>,
+    can_vote: bool,
+) -> impl IntoView {
+    let badge_class = format!(
+        "rounded-full px-2 text-content {}",
+        availability.heat_level().class()
+    );
+    // Options without every required user are listed, but struck through
+    let text_class = if availability.is_viable() {
+        "text-content"
+    } else {
+        "text-faint line-through"
+    };
+    let count = format!("{}/{}", availability.count(), availability.total());
+    let tooltip = availability.tooltip(use_locale());
+
+    let vote_buttons = can_vote.then(|| {
+        [
+            (Vote::Yes, Text::Yes),
+            (Vote::Maybe, Text::Maybe),
+            (Vote::No, Text::No),
+        ]
+        .into_iter()
+        .map(|(vote, label)| {
+            let class = if own_vote == Some(vote) {
+                "rounded-full px-2 bg-control text-content"
+            } else {
+                "rounded-full px-2 border border-line hover:text-content"
+            };
+            let vals = json!({ "option": option.public_id.to_string(), "vote": vote.to_string() });
+
+            view! {
+                <button
+                    type="button"
+                    {..polls_request(Htmx::post(VOTE_ROUTE)).vals(vals).attrs()}
+                    class=class
+                >
+                    {t(label)}
+                </button>
+            }
+        })
+        .collect_view()
+    });
+
+    view! {
+        <li class="flex items-center justify-between border-b border-line py-2">
+            <div>
+                <div class=text_class>{option.text.to_string()}</div>
+                <div class="mt-1 flex space-x-2 text-xs">{vote_buttons}</div>
+            </div>
+            <span class=badge_class title=tooltip>
+                {count}
+            </span>
+        </li>
+    }
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use time::OffsetDateTime;
+
+    use crate::{plan_page::ranking::HeatLevel, test_support::fx_user};
+
+    fn fx_participant(id: i32, name: &str, required: bool) -> Result<UserWithDates> {
+        let mut user = fx_user(id, name)?;
+        user.required = required;
+
+        Ok((user, vec![]))
+    }
+
+    fn fx_vote(user_id: i32, vote: Vote) -> poll_votes::Model {
+        poll_votes::Model {
+            id: 0,
+            option_id: 0,
+            user_id,
+            vote,
+            ctime: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn test_option_availability_from_votes() -> Result<()> {
+        let fx_users = vec![
+            fx_participant(1, "ann", false)?,
+            fx_participant(2, "bob", false)?,
+            fx_participant(3, "carl", true)?,
+            fx_participant(4, "dora", false)?,
+        ];
+        let fx_votes = vec![
+            fx_vote(1, Vote::Yes),
+            fx_vote(2, Vote::Maybe),
+            fx_vote(3, Vote::Yes),
+        ];
+
+        let availability = option_availability(&fx_votes, &fx_users);
+
+        assert_eq!(availability.count(), 2);
+        assert_eq!(availability.total(), 4);
+        assert_eq!(availability.maybe[0].name, "bob");
+        assert_eq!(availability.unavailable[0].name, "dora");
+        assert!(availability.is_viable());
+        // -- 1.5 of the 3 optional users
+        assert_eq!(availability.heat_level(), HeatLevel::Half);
+
+        // -- A required no blocks the option like it blocks a date
+        let fx_votes = vec![fx_vote(1, Vote::Yes), fx_vote(3, Vote::No)];
+        let availability = option_availability(&fx_votes, &fx_users);
+        assert_eq!(availability.heat_level(), HeatLevel::NotViable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_options_needs_two() -> Result<()> {
+        assert_eq!(parse_options(" Pizza \n\n Sushi\r\n", Locale::En)?.len(), 2);
+        assert!(parse_options("Pizza\n \n", Locale::En).is_err());
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,43 @@
+use entity::{db::ModelManager, plans, quorum_dates, types::PublicId, users};
+
+use crate::{
+    error::Result,
+    events::{self, PlanEvent},
+    plan_page::ranking::ranked_dates,
+};
+
+/// The plan's minimum attendance, `None` when it has none
+pub fn min_attendance(plan: &plans::Model) -> Option<usize> {
+    plan.min_attendance
+        .and_then(|min_attendance| usize::try_from(min_attendance).ok())
+        .filter(|min_attendance| *min_attendance > 0)
+}
+
+/// Publishes [`PlanEvent::QuorumReached`] for the dates that reached the quorum for the first time
+pub async fn publish_new_quorums(plan_public_id: PublicId, mm: ModelManager) -> Result<()> {
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let Some(min_attendance) = min_attendance(&plan) else {
+        return Ok(());
+    };
+
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm.clone()).await?;
+    let ranked_dates = ranked_dates(&users_with_dates);
+    for date in ranked_dates.dates() {
+        let availability = ranked_dates.get(&date);
+        if !availability.has_quorum(min_attendance) {
+            continue;
+        }
+
+        if quorum_dates::helpers::record_quorum_reached(plan.id, date, mm.clone()).await? {
+            events::publish(PlanEvent::QuorumReached {
+                plan_public_id: plan.public_id.clone(),
+                date,
+                available: availability.count(),
+                min_attendance,
+            });
+        }
+    }
+
+    Ok(())
+}
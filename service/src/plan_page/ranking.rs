@@ -0,0 +1,328 @@
+//! How available the users of a plan are on each date, shown as a heatmap.
+//!
+//! A date without every required user is not viable, the viable dates are ranked by how many
+//! of the optional users can make it. Poll options are ranked the same way, with a maybe
+//! counting for half.
+
+use std::collections::HashMap;
+
+use time::Date;
+
+use entity::users;
+
+use crate::{
+    i18n::{Locale, Text},
+    plan_page::UserWithDates,
+};
+
+// region:	  --- Ranked dates
+
+/// All users, and who picked which date
+#[derive(Debug, Clone, Default)]
+pub struct RankedDates {
+    users: Vec<Participant>,
+    /// Indices into `users`, as names don't have to be unique
+    available: HashMap<Date, Vec<usize>>,
+}
+
+impl RankedDates {
+    /// The dates anyone picked, in no particular order
+    pub fn dates(&self) -> impl Iterator<Item = Date> + '_ {
+        self.available.keys().copied()
+    }
+
+    /// Who is and isn't available on `date`, also for dates nobody picked
+    pub fn get(&self, date: &Date) -> DateAvailability {
+        let available_users = self
+            .available
+            .get(date)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let (available, unavailable) = self
+            .users
+            .iter()
+            .enumerate()
+            .partition::<Vec<_>, _>(|(index, _)| available_users.contains(index));
+
+        DateAvailability {
+            available: available
+                .into_iter()
+                .map(|(_, participant)| participant.clone())
+                .collect(),
+            unavailable: unavailable
+                .into_iter()
+                .map(|(_, participant)| participant.clone())
+                .collect(),
+            maybe: vec![],
+        }
+    }
+}
+
+pub fn ranked_dates(users_with_dates: &[UserWithDates]) -> RankedDates {
+    let mut available: HashMap<Date, Vec<usize>> = HashMap::new();
+
+    for (index, (_, dates)) in users_with_dates.iter().enumerate() {
+        for date in dates {
+            available.entry(date.date).or_default().push(index);
+        }
+    }
+
+    RankedDates {
+        users: users_with_dates
+            .iter()
+            .map(|(user, _)| Participant::from(user))
+            .collect(),
+        available,
+    }
+}
+
+// endregion: --- Ranked dates
+
+// region:	  --- Date availability
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Participant {
+    pub name: String,
+    pub required: bool,
+}
+
+impl From<&users::Model> for Participant {
+    fn from(user: &users::Model) -> Self {
+        Participant {
+            name: user.name.to_string(),
+            required: user.required,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateAvailability {
+    pub available: Vec<Participant>,
+    pub unavailable: Vec<Participant>,
+    /// Only poll options have maybe votes, they neither count nor block viability
+    pub maybe: Vec<Participant>,
+}
+
+impl DateAvailability {
+    pub fn count(&self) -> usize {
+        self.available.len()
+    }
+
+    pub fn total(&self) -> usize {
+        self.available.len() + self.unavailable.len() + self.maybe.len()
+    }
+
+    /// Required users that can't make it
+    pub fn missing_required(&self) -> impl Iterator<Item = &Participant> {
+        self.unavailable
+            .iter()
+            .filter(|participant| participant.required)
+    }
+
+    /// Every required user can make it
+    pub fn is_viable(&self) -> bool {
+        self.missing_required().next().is_none()
+    }
+
+    /// Share of the optional users that picked the date, 0 for a plan without users.
+    ///
+    /// Without optional users a picked date counts as picked by everyone. A maybe is half a pick.
+    pub fn fraction(&self) -> f32 {
+        let optional = |participants: &[Participant]| {
+            participants
+                .iter()
+                .filter(|participant| !participant.required)
+                .count()
+        };
+        let optional_available =
+            optional(&self.available) as f32 + optional(&self.maybe) as f32 / 2.0;
+
+        match optional(&self.available) + optional(&self.unavailable) + optional(&self.maybe) {
+            0 if self.count() > 0 => 1.0,
+            0 => 0.0,
+            optional_total => optional_available / optional_total as f32,
+        }
+    }
+
+    /// Enough users can make it, including every required one
+    pub fn has_quorum(&self, min_attendance: usize) -> bool {
+        self.is_viable() && self.count() >= min_attendance
+    }
+
+    /// How many more users need to pick the date to reach `min_attendance`
+    pub fn needed_for_quorum(&self, min_attendance: usize) -> usize {
+        min_attendance.saturating_sub(self.count())
+    }
+
+    pub fn heat_level(&self) -> HeatLevel {
+        if self.count() == 0 {
+            return HeatLevel::None;
+        }
+        if !self.is_viable() {
+            return HeatLevel::NotViable;
+        }
+
+        match self.fraction() {
+            fraction if fraction >= 1.0 => HeatLevel::Everyone,
+            fraction if fraction <= 1.0 / 3.0 => HeatLevel::Few,
+            fraction if fraction <= 2.0 / 3.0 => HeatLevel::Half,
+            _ => HeatLevel::Most,
+        }
+    }
+
+    /// Tooltip listing who can and can't make it
+    pub fn tooltip(&self, locale: Locale) -> String {
+        fn names<'a>(participants: impl Iterator<Item = &'a Participant>) -> String {
+            participants
+                .map(|participant| participant.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        let mut lines = vec![];
+        if !self.available.is_empty() {
+            lines.push((Text::Available, names(self.available.iter())));
+        }
+        if !self.maybe.is_empty() {
+            lines.push((Text::Maybe, names(self.maybe.iter())));
+        }
+        if !self.unavailable.is_empty() {
+            lines.push((Text::Unavailable, names(self.unavailable.iter())));
+        }
+        if !self.is_viable() {
+            lines.push((Text::MissingRequired, names(self.missing_required())));
+        }
+
+        lines
+            .into_iter()
+            .map(|(label, names)| format!("{}: {names}", locale.text(label)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Color intensity of a date in the heatmap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatLevel {
+    None,
+    /// Picked, but not by every required user
+    NotViable,
+    Few,
+    Half,
+    Most,
+    Everyone,
+}
+
+impl HeatLevel {
+    /// The levels shown in the legend, from least to most available
+    pub const LEGEND: [HeatLevel; 4] = [
+        HeatLevel::Few,
+        HeatLevel::Half,
+        HeatLevel::Most,
+        HeatLevel::Everyone,
+    ];
+
+    /// Background class, empty when nobody is available
+    pub fn class(self) -> &'static str {
+        match self {
+            HeatLevel::None => "",
+            HeatLevel::NotViable => "border border-heat-2",
+            HeatLevel::Few => "bg-heat-1",
+            HeatLevel::Half => "bg-heat-2",
+            HeatLevel::Most => "bg-heat-3",
+            HeatLevel::Everyone => "bg-heat-4",
+        }
+    }
+}
+
+// endregion: --- Date availability
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+
+    fn fx_participants(count: usize, required: bool) -> Vec<Participant> {
+        (0..count)
+            .map(|i| Participant {
+                name: format!("user{i}"),
+                required,
+            })
+            .collect()
+    }
+
+    fn fx_availability(available: usize, unavailable: usize) -> DateAvailability {
+        DateAvailability {
+            available: fx_participants(available, false),
+            unavailable: fx_participants(unavailable, false),
+            maybe: vec![],
+        }
+    }
+
+    #[test]
+    fn test_date_availability_heat_level() -> Result<()> {
+        let fx_cases = [
+            (0, 0, HeatLevel::None),
+            (0, 10, HeatLevel::None),
+            (1, 9, HeatLevel::Few),
+            (5, 5, HeatLevel::Half),
+            (9, 1, HeatLevel::Most),
+            (10, 0, HeatLevel::Everyone),
+        ];
+
+        for (available, unavailable, expected) in fx_cases {
+            assert_eq!(
+                fx_availability(available, unavailable).heat_level(),
+                expected,
+                "{available} of {}",
+                available + unavailable
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_availability_required() -> Result<()> {
+        // -- A missing required user makes any attendance non-viable
+        let mut fx_missing_required = fx_availability(9, 0);
+        fx_missing_required.unavailable = fx_participants(1, true);
+        assert!(!fx_missing_required.is_viable());
+        assert_eq!(fx_missing_required.heat_level(), HeatLevel::NotViable);
+
+        // -- With the required users there, optional attendance ranks the date
+        let mut fx_required_there = fx_availability(1, 3);
+        fx_required_there.available.extend(fx_participants(2, true));
+        assert!(fx_required_there.is_viable());
+        assert_eq!(fx_required_there.heat_level(), HeatLevel::Few);
+
+        // -- Only required users, all there
+        let fx_only_required = DateAvailability {
+            available: fx_participants(2, true),
+            unavailable: vec![],
+            maybe: vec![],
+        };
+        assert_eq!(fx_only_required.heat_level(), HeatLevel::Everyone);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_availability_quorum() -> Result<()> {
+        let fx_availability = fx_availability(3, 5);
+        assert!(fx_availability.has_quorum(3));
+        assert!(!fx_availability.has_quorum(4));
+        assert_eq!(fx_availability.needed_for_quorum(5), 2);
+        assert_eq!(fx_availability.needed_for_quorum(2), 0);
+
+        // -- A missing required user blocks the quorum, however many come
+        let mut fx_missing_required = fx_availability.clone();
+        fx_missing_required.unavailable = fx_participants(1, true);
+        assert!(!fx_missing_required.has_quorum(3));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,170 @@
+//! Plans that happen every month: the owner copies the plan with its participants and settings,
+//! as it is or for the month after it.
+
+use axum::{
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    routing::post,
+    Form, Router,
+};
+use axum_extra::extract::CookieJar;
+use axum_htmx::HxRequest;
+use entity::{db::ModelManager, plans, types::PublicId, users};
+use http::Uri;
+use leptos::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use time::Date;
+use tracing::debug;
+
+use crate::{
+    error::Result,
+    htmx_helpers::{Htmx, HtmxRoute},
+    i18n::{t, Text},
+    middleware::mw_rate_limit::{mw_rate_limit, RateLimiter},
+    plan_page::{
+        calendar::CalendarSettings,
+        owner::{self, require_plan_owner},
+        CreatePlanResponse, UserWithDates,
+    },
+    request_context::RequestContext,
+};
+
+pub const CLONE_ROUTE: HtmxRoute = HtmxRoute::plan("/clone");
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route(
+            CLONE_ROUTE.path(),
+            post(clone_plan_handler)
+                .layer(from_fn_with_state(RateLimiter::creation(), mw_rate_limit)),
+        )
+        .with_state(mm)
+}
+
+// region:	  --- Clone handler
+
+#[derive(Debug, Deserialize)]
+struct ClonePost {
+    /// Shifts the window of the copy to the month after this plan's
+    #[serde(default)]
+    next_month: bool,
+}
+
+async fn clone_plan_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    HxRequest(htmx): HxRequest,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(clone_post): Form<ClonePost>,
+) -> Result<CreatePlanResponse> {
+    debug!(
+        "{:<12} - clone_plan - {plan_public_id} - {}",
+        "HANDLER", clone_post.next_month
+    );
+
+    // -- Only the owner can copy the emails of the participants
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    let window_start = match clone_post.next_month {
+        true => {
+            let users_with_dates = users::helpers::get_users_with_date_for_plan_public_id(
+                plan_public_id.clone(),
+                mm.clone(),
+            )
+            .await?;
+            let today = CalendarSettings::new(&plan, &context).today;
+            Some(next_window_start(&plan, &users_with_dates, today))
+        }
+        false => plan.window_start,
+    };
+    let new_plan = plans::helpers::clone_plan(plan_public_id, window_start, mm).await?;
+
+    Ok(CreatePlanResponse {
+        plan_url: format!("/plan/{}/", new_plan.public_id).parse::<Uri>()?,
+        owner_cookie: owner::owner_cookie(&new_plan),
+        htmx,
+    })
+}
+
+// endregion: --- Clone handler
+
+/// The first day of the month after the one the plan is about. That is its window, or the
+/// month of its final date or of its earliest candidate, and otherwise the current month.
+fn next_window_start(plan: &plans::Model, users_with_dates: &[UserWithDates], today: Date) -> Date {
+    let earliest_candidate = users_with_dates
+        .iter()
+        .flat_map(|(_, dates)| dates.iter().map(|date_model| date_model.date))
+        .min();
+    let current = plan
+        .window_start
+        .or(plan.final_date)
+        .or(earliest_candidate)
+        .unwrap_or(today);
+
+    let (year, month) = match current.month() {
+        time::Month::December => (current.year() + 1, time::Month::January),
+        month => (current.year(), month.next()),
+    };
+    // The first of a month always exists
+    Date::from_calendar_date(year, month, 1).unwrap_or(current)
+}
+
+/// Copies of the plan without the dates, only shown to owners
+#[component]
+pub fn RepeatPlan() -> impl IntoView {
+    let button_class = "rounded-lg border border-line px-3 py-1 hover:text-content";
+
+    view! {
+        <div class="mx-auto max-w-80 my-8 text-sm text-muted">
+            <div class="flex justify-center space-x-2">
+                <button
+                    type="button"
+                    {..Htmx::post(CLONE_ROUTE).vals(json!({ "next_month": true })).attrs()}
+                    class=button_class
+                >
+                    {t(Text::RepeatNextMonth)}
+                </button>
+                <button type="button" {..Htmx::post(CLONE_ROUTE).attrs()} class=button_class>
+                    {t(Text::ClonePlan)}
+                </button>
+            </div>
+            <p class="mt-2 text-center text-xs">{t(Text::ClonePlanHint)}</p>
+        </div>
+    }
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use time::Month;
+
+    use crate::test_support::fx_plan;
+
+    #[test]
+    fn test_next_window_start_after_final_date() -> Result<()> {
+        let fx_today = Date::from_calendar_date(2026, Month::October, 19)?;
+        let mut fx_plan = fx_plan("Book club")?;
+
+        // -- Nothing picked yet, the month after this one
+        assert_eq!(
+            next_window_start(&fx_plan, &[], fx_today),
+            Date::from_calendar_date(2026, Month::November, 1)?
+        );
+
+        // -- The final date wins, also over the end of the year
+        fx_plan.final_date = Some(Date::from_calendar_date(2026, Month::December, 17)?);
+        assert_eq!(
+            next_window_start(&fx_plan, &[], fx_today),
+            Date::from_calendar_date(2027, Month::January, 1)?
+        );
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,236 @@
+use axum::{
+    extract::{Path, State},
+    response::{Html, IntoResponse, Response},
+    routing::post,
+    Form, Router,
+};
+use axum_extra::extract::CookieJar;
+use axum_htmx::SwapOption;
+use entity::{
+    comments,
+    db::ModelManager,
+    plans,
+    sea_orm::Iterable,
+    types::{deserialize_public_id_option, PublicId, TimeZoneName, WeekStart},
+    users,
+};
+use leptos::prelude::*;
+use serde::{de::IntoDeserializer, Deserialize, Deserializer};
+use time::Month;
+use tracing::debug;
+
+use crate::{
+    error::Result,
+    htmx_helpers::{Htmx, HtmxInclude, HtmxRoute},
+    i18n::{t, use_locale, Text},
+    plan_page::{
+        calendar::{deserialize_month, Calendar, CalendarMonth, CalendarSettings, CalendarView},
+        comments::date_notes,
+        filter_users_with_dates, htmx_ids,
+        owner::require_plan_owner,
+        quorum,
+    },
+    request_context::RequestContext,
+};
+
+pub const SETTINGS_ROUTE: HtmxRoute = HtmxRoute::plan("/settings");
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route(SETTINGS_ROUTE.path(), post(update_settings_handler))
+        .with_state(mm)
+}
+
+// region:	  --- Settings handler
+
+#[derive(Debug, Deserialize)]
+struct SettingsPost {
+    #[serde(deserialize_with = "deserialize_week_start_option")]
+    week_start: Option<WeekStart>,
+    /// Unchecked checkboxes are not sent at all
+    #[serde(default)]
+    show_week_numbers: bool,
+    time_zone: TimeZoneName,
+    #[serde(default, deserialize_with = "deserialize_min_attendance")]
+    min_attendance: Option<i32>,
+    // -- The calendar to render with the new settings
+    #[serde(deserialize_with = "deserialize_month")]
+    month: Month,
+    year: i32,
+    #[serde(default, deserialize_with = "deserialize_public_id_option")]
+    user_public_id: Option<PublicId>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+async fn update_settings_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(settings_post): Form<SettingsPost>,
+) -> Result<Response> {
+    debug!("{:<12} - update_settings - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    let plan = plans::helpers::update_calendar_settings(
+        plan_public_id.clone(),
+        settings_post.week_start,
+        settings_post.show_week_numbers,
+        settings_post.time_zone,
+        settings_post.min_attendance,
+        mm.clone(),
+    )
+    .await?;
+    // A lower minimum can make dates reach the quorum right away
+    quorum::publish_new_quorums(plan_public_id.clone(), mm.clone()).await?;
+
+    // -- Re-render the calendar as it was shown
+    let comments =
+        comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+    let current_user_with_dates = settings_post
+        .user_public_id
+        .and_then(|user_public_id| filter_users_with_dates(&users_with_dates, user_public_id));
+    let calendar_month = CalendarMonth::new(settings_post.month, settings_post.year);
+    let settings = CalendarSettings::new(&plan, &context);
+
+    let view = context.render(|| {
+        view! {
+            <Calendar
+                users_with_dates=users_with_dates
+                current_user_with_dates=current_user_with_dates
+                calendar_month=calendar_month
+                calendar_view=settings_post.view
+                settings=settings
+                date_notes=date_notes(&comments)
+            />
+        }
+    });
+    Ok(Html(view).into_response())
+}
+
+/// The empty option stands for the locale's default
+fn deserialize_week_start_option<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<WeekStart>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)?.as_deref() {
+        None | Some("") => Ok(None),
+        Some(s) => WeekStart::deserialize(s.into_deserializer()).map(Some),
+    }
+}
+
+/// The empty input stands for no minimum attendance
+fn deserialize_min_attendance<'de, D>(deserializer: D) -> std::result::Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)?
+        .as_deref()
+        .map(str::trim)
+    {
+        None | Some("") => Ok(None),
+        Some(s) => s
+            .parse::<u16>()
+            .map(|min_attendance| Some(min_attendance.into()))
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+// endregion: --- Settings handler
+
+#[component]
+pub fn PlanSettings(
+    week_start: Option<WeekStart>,
+    show_week_numbers: bool,
+    time_zone: TimeZoneName,
+    min_attendance: Option<i32>,
+) -> impl IntoView {
+    let locale = use_locale();
+
+    let include_targets = HtmxInclude::from(vec![
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::CALENDAR_VIEW.clone(),
+        htmx_ids::USER_PUBLIC_ID.clone(),
+    ]);
+
+    view! {
+        <details class="mx-auto max-w-80 my-8 text-sm text-muted">
+            <summary>{t(Text::CalendarSettings)}</summary>
+            <form
+                {..Htmx::post(SETTINGS_ROUTE)
+                    .target(htmx_ids::CALENDAR_ID.clone())
+                    .swap(SwapOption::OuterHtml)
+                    .include(include_targets)
+                    .attrs()}
+                class="mt-4 space-y-2"
+            >
+                <label class="flex items-center justify-between">
+                    {t(Text::WeekStartsOn)}
+                    <select
+                        name="week_start"
+                        class="rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                    >
+                        <option value="" selected=week_start.is_none()>
+                            {t(Text::LanguageDefault)}
+                        </option>
+                        {WeekStart::iter()
+                            .map(|option| {
+                                view! {
+                                    <option
+                                        value=option.to_string()
+                                        selected=week_start == Some(option)
+                                    >
+                                        {locale.weekday_name(option.into())}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label class="flex items-center justify-between">
+                    {t(Text::ShowWeekNumbers)}
+                    <input
+                        type="checkbox"
+                        name="show_week_numbers"
+                        value="true"
+                        checked=show_week_numbers
+                    />
+                </label>
+                <label class="flex items-center justify-between">
+                    {t(Text::TimeZone)}
+                    <input
+                        type="text"
+                        name="time_zone"
+                        value=time_zone.to_string()
+                        required
+                        class="rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                    />
+                </label>
+                <label class="flex items-center justify-between">
+                    {t(Text::MinimumAttendance)}
+                    <input
+                        type="number"
+                        name="min_attendance"
+                        min="1"
+                        value=min_attendance.map(|min_attendance| min_attendance.to_string())
+                        class="w-20 rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                    />
+                </label>
+                <button
+                    type="submit"
+                    class="mb-2 w-full rounded-lg bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
+                >
+                    {t(Text::Save)}
+                </button>
+            </form>
+        </details>
+    }
+}
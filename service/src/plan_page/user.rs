@@ -1,67 +1,121 @@
 use crate::{
+    config::web_config,
     error::Result,
-    htmx_helpers::{HtmxId, HtmxInclude, HtmxInput, HtmxTarget},
+    events::{self, PlanEvent},
+    htmx_helpers::{Htmx, HtmxId, HtmxInclude, HtmxInput, HtmxRoute, HtmxSwapOob},
+    i18n::{t, Text},
+    middleware::mw_rate_limit::{mw_rate_limit, RateLimiter},
     plan_page::{
-        calendar::{Calendar, CalendarMonth},
+        calendar::{
+            deserialize_month_option, Calendar, CalendarMonth, CalendarSettings, CalendarView,
+        },
+        comments::{date_notes, DateNotes},
         htmx_ids,
+        owner::{is_plan_owner, require_plan_owner},
+        polls::Polls,
+        quorum,
     },
-    util_components::{HtmxHiddenInput, HtmxSwapOob, Icon},
+    request_context::RequestContext,
+    util_components::{CopyToClipboard, CsrfField, HtmxHiddenInput, Icon},
 };
 use axum::{
     extract::{Path, Query, State},
+    middleware::from_fn_with_state,
     response::{Html, IntoResponse, Response},
     routing::post,
     Form, Router,
 };
+use axum_extra::extract::CookieJar;
+use axum_htmx::{HxRequest, SwapOption};
 use entity::{
+    comments, dates,
     db::ModelManager,
-    types::{PublicId, UserName},
+    plans,
+    poll_questions::{self, PollWithOptions},
+    types::{
+        deserialize_email_option, deserialize_public_id_option, EmailAddress, PublicId, UserName,
+    },
     users::{self},
 };
 use http::StatusCode;
 use leptos::{either::Either, prelude::*};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
+use serde_json::json;
+use time::Month;
 use tracing::debug;
 
-use super::{filter_users_with_dates, UserWithDates};
+use super::{filter_users_with_dates, plan_page_redirect, UserWithDates};
+
+pub const USER_ROUTE: HtmxRoute = HtmxRoute::plan("/user");
+pub const REQUIRED_ROUTE: HtmxRoute = HtmxRoute::plan("/user/required");
+pub const IMPORT_ROUTE: HtmxRoute = HtmxRoute::plan("/user/import");
 
 pub fn routes(mm: ModelManager) -> Router<entity::db::ModelManager> {
-    Router::new().nest(
-        "/user",
-        Router::new()
-            .route("/", post(create_user_handler).get(change_user_handler))
-            .with_state(mm),
-    )
+    Router::new()
+        .route(
+            USER_ROUTE.path(),
+            post(create_user_handler)
+                .layer(from_fn_with_state(RateLimiter::creation(), mw_rate_limit))
+                .get(change_user_handler),
+        )
+        .route(REQUIRED_ROUTE.path(), post(set_required_handler))
+        .route(IMPORT_ROUTE.path(), post(import_dates_handler))
+        .with_state(mm)
 }
 
 // region:	  --- User handlers
 #[derive(Debug, Deserialize)]
 struct UserPost {
     username: UserName,
+    /// Only asked for when email notifications are on
+    #[serde(default, deserialize_with = "deserialize_email_option")]
+    email: Option<EmailAddress>,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
 }
 
 #[derive(Debug)]
 struct UpdateUserResponse {
-    current_user_public_id: PublicId,
+    current_user_public_id: Option<PublicId>,
     users_with_dates: Vec<UserWithDates>,
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    calendar_settings: CalendarSettings,
+    date_notes: DateNotes,
+    polls: Vec<PollWithOptions>,
+    is_owner: bool,
+    context: RequestContext,
 }
 
 impl IntoResponse for UpdateUserResponse {
     fn into_response(self) -> Response {
         let status = StatusCode::OK;
         let current_user_with_dates =
-            filter_users_with_dates(&self.users_with_dates, self.current_user_public_id.clone());
-        let view = Html(
+            self.current_user_public_id
+                .clone()
+                .and_then(|current_user_public_id| {
+                    filter_users_with_dates(&self.users_with_dates, current_user_public_id)
+                });
+        let view = Html(self.context.render(|| {
             view! {
                 <UsersUpdate
                     users_with_dates=self.users_with_dates
                     current_user_public_id=self.current_user_public_id
                     current_user_with_dates
+                    calendar_month=self.calendar_month
+                    calendar_view=self.calendar_view
+                    calendar_settings=self.calendar_settings
+                    date_notes=self.date_notes
+                    polls=self.polls
+                    is_owner=self.is_owner
                 />
             }
-            .to_html(),
-        );
+        }));
 
         (status, view).into_response()
     }
@@ -70,6 +124,9 @@ impl IntoResponse for UpdateUserResponse {
 async fn create_user_handler(
     State(mm): State<ModelManager>,
     Path(plan_public_id): Path<PublicId>,
+    HxRequest(htmx): HxRequest,
+    jar: CookieJar,
+    context: RequestContext,
     Form(user_post): Form<UserPost>,
 ) -> Result<impl IntoResponse> {
     debug!(
@@ -77,6 +134,8 @@ async fn create_user_handler(
         "HANDLER", user_post.username
     );
 
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+
     // -- Create new user
     let new_user = users::helpers::create_user_for_plan(
         plan_public_id.clone(),
@@ -84,14 +143,55 @@ async fn create_user_handler(
         mm.clone(),
     )
     .await?;
+    if let Some(email) = user_post.email {
+        users::helpers::set_email(
+            new_user.public_id.clone(),
+            email,
+            context.locale.to_string(),
+            mm.clone(),
+        )
+        .await?;
+    }
+    events::publish(PlanEvent::UserJoined {
+        plan_public_id: plan_public_id.clone(),
+        user_public_id: new_user.public_id.clone(),
+    });
+
+    // -- Without htmx the whole page is loaded again, with the new user editing
+    if !htmx {
+        let calendar_month = CalendarMonth::from_query(user_post.month, user_post.year);
+        return Ok(plan_page_redirect(
+            &plan_public_id,
+            &new_user.public_id,
+            calendar_month,
+            user_post.view,
+        )
+        .into_response());
+    }
 
     //-- Get all users with their dates to use for result
+    let comments =
+        comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let polls =
+        poll_questions::helpers::polls_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
     let users_with_dates =
         users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
 
+    let calendar_settings = CalendarSettings::new(&plan, &context);
+    let calendar_month = CalendarMonth::from_query(user_post.month, user_post.year)
+        .unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &calendar_settings));
+
     Ok(UpdateUserResponse {
         users_with_dates,
-        current_user_public_id: new_user.public_id,
+        current_user_public_id: Some(new_user.public_id),
+        calendar_month,
+        calendar_view: user_post.view,
+        calendar_settings,
+        date_notes: date_notes(&comments),
+        polls,
+        is_owner: is_plan_owner(&plan, &jar),
+        context,
     }
     .into_response())
 }
@@ -99,11 +199,19 @@ async fn create_user_handler(
 #[derive(Debug, Deserialize)]
 struct UserGet {
     user_public_id: PublicId,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
 }
 
 async fn change_user_handler(
     State(mm): State<ModelManager>,
     Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
     Query(user_get): Query<UserGet>,
 ) -> Result<impl IntoResponse> {
     debug!(
@@ -111,13 +219,176 @@ async fn change_user_handler(
         "HANDLER", user_get.user_public_id
     );
 
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+
     //-- Get all users with their dates to use for result
+    let comments =
+        comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let polls =
+        poll_questions::helpers::polls_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
     let users_with_dates =
         users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
 
+    let calendar_settings = CalendarSettings::new(&plan, &context);
+    let calendar_month = CalendarMonth::from_query(user_get.month, user_get.year)
+        .unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &calendar_settings));
+
     Ok(UpdateUserResponse {
         users_with_dates,
-        current_user_public_id: user_get.user_public_id,
+        current_user_public_id: Some(user_get.user_public_id),
+        calendar_month,
+        calendar_view: user_get.view,
+        calendar_settings,
+        date_notes: date_notes(&comments),
+        polls,
+        is_owner: is_plan_owner(&plan, &jar),
+        context,
+    }
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct RequiredPost {
+    /// The user to mark, not necessarily the one editing
+    participant: PublicId,
+    required: bool,
+    #[serde(default, deserialize_with = "deserialize_public_id_option")]
+    user_public_id: Option<PublicId>,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+async fn set_required_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(required_post): Form<RequiredPost>,
+) -> Result<impl IntoResponse> {
+    debug!(
+        "{:<12} - set_required_handler - {} - {}",
+        "HANDLER", required_post.participant, required_post.required
+    );
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    users::helpers::set_required(
+        plan_public_id.clone(),
+        required_post.participant,
+        required_post.required,
+        mm.clone(),
+    )
+    .await?;
+    // Making someone optional can let a date reach the quorum
+    quorum::publish_new_quorums(plan_public_id.clone(), mm.clone()).await?;
+
+    //-- Get all users with their dates to use for result
+    let comments =
+        comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let polls =
+        poll_questions::helpers::polls_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+
+    let calendar_settings = CalendarSettings::new(&plan, &context);
+    let calendar_month = CalendarMonth::from_query(required_post.month, required_post.year)
+        .unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &calendar_settings));
+
+    Ok(UpdateUserResponse {
+        users_with_dates,
+        current_user_public_id: required_post.user_public_id,
+        calendar_month,
+        calendar_view: required_post.view,
+        calendar_settings,
+        date_notes: date_notes(&comments),
+        polls,
+        is_owner: true,
+        context,
+    }
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportPost {
+    /// The same person in another plan
+    source: PublicId,
+    user_public_id: PublicId,
+    // -- The month the calendar is on
+    #[serde(default, deserialize_with = "deserialize_month_option")]
+    month: Option<Month>,
+    year: Option<i32>,
+    #[serde(default)]
+    view: CalendarView,
+}
+
+/// Copies the dates of the user in another plan that fall in the window of this one
+async fn import_dates_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(import_post): Form<ImportPost>,
+) -> Result<impl IntoResponse> {
+    debug!(
+        "{:<12} - import_dates_handler - {} - {}",
+        "HANDLER", import_post.source, import_post.user_public_id
+    );
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let user =
+        users::helpers::user_in_plan(plan.id, import_post.user_public_id.clone(), mm.clone())
+            .await?;
+
+    let calendar_settings = CalendarSettings::new(&plan, &context);
+    let source_dates = dates::helpers::user_dates(import_post.source, mm.clone()).await?;
+    let added_dates = dates::helpers::user_add_dates(
+        user.public_id.clone(),
+        source_dates
+            .into_iter()
+            .filter(|date| calendar_settings.in_window(*date))
+            .collect(),
+        mm.clone(),
+    )
+    .await?;
+    if !added_dates.is_empty() {
+        events::publish(PlanEvent::AvailabilityChanged {
+            plan_public_id: plan_public_id.clone(),
+            user_public_id: user.public_id.clone(),
+            dates: added_dates,
+            available: true,
+        });
+    }
+    quorum::publish_new_quorums(plan_public_id.clone(), mm.clone()).await?;
+
+    //-- Get all users with their dates to use for result
+    let comments =
+        comments::helpers::comments_for_plan_public_id(plan_public_id.clone(), mm.clone()).await?;
+    let polls =
+        poll_questions::helpers::polls_for_plan_public_id(plan_public_id.clone(), mm.clone())
+            .await?;
+    let users_with_dates =
+        users::helpers::get_users_with_date_for_plan_public_id(plan_public_id, mm).await?;
+
+    let calendar_month = CalendarMonth::from_query(import_post.month, import_post.year)
+        .unwrap_or_else(|| CalendarMonth::initial(&users_with_dates, &calendar_settings));
+
+    Ok(UpdateUserResponse {
+        users_with_dates,
+        current_user_public_id: Some(user.public_id),
+        calendar_month,
+        calendar_view: import_post.view,
+        calendar_settings,
+        date_notes: date_notes(&comments),
+        polls,
+        is_owner: is_plan_owner(&plan, &jar),
+        context,
     }
     .into_response())
 }
@@ -126,24 +397,40 @@ async fn change_user_handler(
 #[component]
 fn UsersUpdate(
     users_with_dates: Vec<UserWithDates>,
-    current_user_public_id: PublicId,
+    current_user_public_id: Option<PublicId>,
     current_user_with_dates: Option<UserWithDates>,
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    calendar_settings: CalendarSettings,
+    date_notes: DateNotes,
+    polls: Vec<PollWithOptions>,
+    is_owner: bool,
 ) -> impl IntoView {
-    let calendar_month = CalendarMonth::current_month();
-    let calender_id = htmx_ids::CALENDAR_ID.clone();
-
     view! {
         <Users
             users_with_dates=users_with_dates.clone()
-            current_user=Some(current_user_public_id.clone())
+            current_user=current_user_public_id.clone()
+            calendar_month=calendar_month
+            calendar_view=calendar_view
+            is_owner=is_owner
+        />
+        // The polls only show the vote buttons for the current user
+        <Polls
+            polls=polls
+            users_with_dates=users_with_dates.clone()
+            current_user=current_user_public_id
+            is_owner=is_owner
+            swap_oob=HtmxSwapOob::OuterHtml
+        />
+        <Calendar
+            users_with_dates=users_with_dates
+            calendar_month=calendar_month
+            calendar_view=calendar_view
+            current_user_with_dates=current_user_with_dates
+            settings=calendar_settings
+            date_notes=date_notes
+            swap_oob=HtmxSwapOob::OuterHtml
         />
-        <HtmxSwapOob id=calender_id>
-            <Calendar
-                users_with_dates=users_with_dates
-                calendar_month=calendar_month
-                current_user_with_dates=current_user_with_dates
-            />
-        </HtmxSwapOob>
     }
 }
 
@@ -152,6 +439,11 @@ static USERS_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("users"));
 pub fn Users(
     users_with_dates: Vec<UserWithDates>,
     current_user: Option<PublicId>,
+    /// Switching users keeps the calendar on this month and view
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    /// Owners can mark users as required
+    is_owner: bool,
 ) -> impl IntoView {
     let user_public_id = match current_user.clone() {
         Some(public_id) => public_id.to_string(),
@@ -174,64 +466,167 @@ pub fn Users(
         current_user
     }) {
         Either::Left(view! {
-            <div id="users">
+            <div id=USERS_ID.to_string()>
                 <HtmxHiddenInput input=htmx_ids::USER_PUBLIC_ID.clone() value=user_public_id/>
-                <UserListWithActiveUser other_users=users current_user=current_user/>
+                <UserListWithActiveUser
+                    other_users=users
+                    current_user=current_user
+                    calendar_month=calendar_month
+                    calendar_view=calendar_view
+                    is_owner=is_owner
+                />
             </div>
         })
     } else {
         Either::Right(view! {
-            <div id="users">
+            <div id=USERS_ID.to_string()>
                 <HtmxHiddenInput input=htmx_ids::USER_PUBLIC_ID.clone() value=user_public_id/>
-                <UserListNoActiveUser users=users/>
+                <UserListNoActiveUser
+                    users=users
+                    calendar_month=calendar_month
+                    calendar_view=calendar_view
+                    is_owner=is_owner
+                />
             </div>
         })
     }
 }
 
 #[component]
-fn CurrentUser(user: users::Model) -> impl IntoView {
+fn CurrentUser(user: users::Model, is_owner: bool) -> impl IntoView {
     let username = user.name.to_string();
     view! {
-        <li class="flex justify-between items-center border-b border-gray-700 py-2">
-            <span class="text-white font-bold">{username}</span>
+        <li class="flex justify-between items-center border-b border-line py-2">
+            <span class="text-content font-bold">{username}</span>
+            <RequiredToggle user=user.clone() is_owner=is_owner/>
+        </li>
+        <ImportDates user=user/>
+    }
+}
+
+/// Copies the user's dates from another plan, where they have a code of their own
+#[component]
+fn ImportDates(user: users::Model) -> impl IntoView {
+    let include_targets = HtmxInclude::from(vec![
+        htmx_ids::USER_PUBLIC_ID.clone(),
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::CALENDAR_VIEW.clone(),
+    ]);
+
+    view! {
+        <li class="border-b border-line py-2 text-xs text-muted">
+            <details>
+                <summary>{t(Text::ImportDates)}</summary>
+                <p class="mt-2">
+                    {t(Text::ImportDatesHint)} " "
+                    <CopyToClipboard value=user.public_id.clone()>
+                        <code class="text-content">{user.public_id.to_string()}</code>
+                    </CopyToClipboard>
+                </p>
+                <form
+                    {..Htmx::post(IMPORT_ROUTE)
+                        .include(include_targets)
+                        .target(USERS_ID.clone())
+                        .swap(SwapOption::OuterHtml)
+                        .attrs()}
+                    class="mt-2 flex space-x-2"
+                >
+                    <input
+                        type="text"
+                        name="source"
+                        required
+                        placeholder=t(Text::ImportCodePlaceholder)
+                        class="w-full rounded-lg border border-line bg-transparent px-2 py-1 text-content"
+                    />
+                    <button type="submit" class="rounded-lg bg-control px-3 py-1 text-content hover:bg-control-hover">
+                        {t(Text::Import)}
+                    </button>
+                </form>
+            </details>
         </li>
     }
 }
 
+/// Shows whether a user is required, which the owner can switch
+#[component]
+fn RequiredToggle(user: users::Model, is_owner: bool) -> impl IntoView {
+    let (class, label) = if user.required {
+        (
+            "rounded-full px-2 text-xs text-content bg-heat-2",
+            t(Text::Required),
+        )
+    } else {
+        (
+            "rounded-full px-2 text-xs text-muted border border-line",
+            t(Text::Optional),
+        )
+    };
+
+    if !is_owner {
+        return user
+            .required
+            .then(|| view! { <span class=class>{label}</span> })
+            .into_any();
+    }
+
+    let title = if user.required {
+        t(Text::MakeOptional)
+    } else {
+        t(Text::MakeRequired)
+    };
+    let vals = json!({
+        "participant": user.public_id.to_string(),
+        "required": (!user.required).to_string(),
+    });
+    let include_targets = HtmxInclude::from(vec![
+        htmx_ids::USER_PUBLIC_ID.clone(),
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::CALENDAR_VIEW.clone(),
+    ]);
+
+    view! {
+        <button
+            type="button"
+            {..Htmx::post(REQUIRED_ROUTE)
+                .vals(vals)
+                .include(include_targets)
+                .target(USERS_ID.clone())
+                .swap(SwapOption::OuterHtml)
+                .attrs()}
+            class=class
+            title=title
+        >
+            {label}
+        </button>
+    }
+    .into_any()
+}
+
 #[component]
 fn UserListWithActiveUser(
     /// The list of all users *except* the currently active one
     other_users: Vec<users::Model>,
     /// Currently active user
     current_user: users::Model,
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    is_owner: bool,
 ) -> impl IntoView {
     view! {
         <ul class="mx-auto max-w-80 mt-4 space-y-2">
-            <CurrentUser user=current_user/>
+            <CurrentUser user=current_user is_owner=is_owner/>
             {other_users
                 .into_iter()
                 .map(|user| {
-                    let username = user.name.to_string();
-                    let input = HtmxInput::new(
-                        HtmxId::new(&format!("user{}", &user.public_id)),
-                        "user_public_id",
-                    );
-                    let include = HtmxInclude::from(input.clone()).to_string();
-                    let target = HtmxTarget::from(USERS_ID.clone()).to_string();
                     view! {
-                        <li class="flex justify-between items-center border-b border-gray-700 py-2">
-                            <HtmxHiddenInput input=input value=user.public_id/>
-                            <span class="text-white">{username}</span>
-                            <button
-                                hx-get="user"
-                                hx-target=target
-                                hx-include=include
-                                class="p-2 text-gray-400 hover:text-white"
-                            >
-                                <Icon icon=Icon::Edit/>
-                            </button>
-                        </li>
+                        <UserRow
+                            user=user
+                            calendar_month=calendar_month
+                            calendar_view=calendar_view
+                            is_owner=is_owner
+                        />
                     }
                 })
                 .collect_view()}
@@ -243,7 +638,12 @@ fn UserListWithActiveUser(
 }
 
 #[component]
-fn UserListNoActiveUser(users: Vec<users::Model>) -> impl IntoView {
+fn UserListNoActiveUser(
+    users: Vec<users::Model>,
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    is_owner: bool,
+) -> impl IntoView {
     view! {
         <ul class="mx-auto max-w-80 mt-4 space-y-2">
             <li class="flex justify-between items-center">
@@ -252,26 +652,13 @@ fn UserListNoActiveUser(users: Vec<users::Model>) -> impl IntoView {
             {users
                 .into_iter()
                 .map(|user| {
-                    let username = user.name.to_string();
-                    let input = HtmxInput::new(
-                        HtmxId::new(&format!("user{}", &user.public_id)),
-                        "user_public_id",
-                    );
-                    let include = HtmxInclude::from(input.clone()).to_string();
-                    let target = HtmxTarget::from(USERS_ID.clone()).to_string();
                     view! {
-                        <li class="flex justify-between items-center border-b border-gray-700 py-2">
-                            <HtmxHiddenInput input=input value=user.public_id/>
-                            <span class="text-white">{username}</span>
-                            <button
-                                hx-get="user"
-                                hx-target=target
-                                hx-include=include
-                                class="p-2 text-gray-400 hover:text-white"
-                            >
-                                <Icon icon=Icon::Edit/>
-                            </button>
-                        </li>
+                        <UserRow
+                            user=user
+                            calendar_month=calendar_month
+                            calendar_view=calendar_view
+                            is_owner=is_owner
+                        />
                     }
                 })
                 .collect_view()}
@@ -279,31 +666,197 @@ fn UserListNoActiveUser(users: Vec<users::Model>) -> impl IntoView {
     }
 }
 
+/// A user other than the current one, with a link to continue as them
+#[component]
+fn UserRow(
+    user: users::Model,
+    calendar_month: CalendarMonth,
+    calendar_view: CalendarView,
+    is_owner: bool,
+) -> impl IntoView {
+    let username = user.name.to_string();
+    let input = HtmxInput::new(
+        HtmxId::new(&format!("user{}", &user.public_id)),
+        "user_public_id",
+    );
+    let include = HtmxInclude::from(vec![
+        input.clone(),
+        htmx_ids::CALENDAR_MONTH.clone(),
+        htmx_ids::CALENDAR_YEAR.clone(),
+        htmx_ids::CALENDAR_VIEW.clone(),
+    ]);
+
+    view! {
+        <li class="flex justify-between items-center border-b border-line py-2">
+            <HtmxHiddenInput input=input value=user.public_id.clone()/>
+            <span class="text-content">{username}</span>
+            <RequiredToggle user=user.clone() is_owner=is_owner/>
+            <a
+                href=calendar_month.page_query(calendar_view, Some(&user.public_id))
+                {..Htmx::get(USER_ROUTE).target(USERS_ID.clone()).include(include).attrs()}
+                class="p-2 text-muted hover:text-content"
+            >
+                <Icon icon=Icon::Edit/>
+            </a>
+        </li>
+    }
+}
+
 #[component]
 fn UserInput() -> impl IntoView {
+    let email_input = web_config().SMTP_URL.is_some().then(|| {
+        view! {
+            <input
+                type="email"
+                name="email"
+                class="border-1 mt-2 block w-full appearance-none rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content outline-none focus:border-content "
+                placeholder=t(Text::EmailPlaceholder)
+            />
+        }
+    });
+
     view! {
         <form
-            hx-post="user"
-            hx-target=HtmxTarget::from(USERS_ID.clone()).to_string()
-            hx-swap="outerHTML"
+            method="post"
+            action=USER_ROUTE.to_string()
+            {..Htmx::post(USER_ROUTE)
+                .target(USERS_ID.clone())
+                .swap(SwapOption::OuterHtml)
+                .include(vec![
+                    htmx_ids::CALENDAR_MONTH.clone(),
+                    htmx_ids::CALENDAR_YEAR.clone(),
+                    htmx_ids::CALENDAR_VIEW.clone(),
+                ])
+                .attrs()}
             class="container mx-auto flex max-w-80 justify-between"
         >
+            <CsrfField/>
             <div>
                 <input
                     type="text"
                     id="username"
                     name="username"
-                    class="border-1 peer block w-full appearance-none rounded-lg border border-gray-600 bg-transparent px-2 py-2.5 text-sm text-white outline-none focus:border-gray-300 "
-                    placeholder="Your name"
+                    class="border-1 peer block w-full appearance-none rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content outline-none focus:border-content "
+                    placeholder=t(Text::UsernamePlaceholder)
                 />
+                {email_input}
             </div>
             <button
                 type="submit"
-                class="mb-2 me-2 flex rounded-lg bg-gray-600 px-5 py-2.5 text-sm font-medium text-white hover:bg-gray-700"
+                class="mb-2 me-2 flex rounded-lg bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
             >
-                "Create"
+                {t(Text::Create)}
             </button>
 
         </form>
     }
 }
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+    use entity::{
+        poll_options,
+        types::{PollOptionText, PollQuestionText},
+    };
+    use std::collections::HashSet;
+    use time::{Date, OffsetDateTime, Weekday};
+
+    use crate::test_support::fx_user_with_dates;
+
+    fn fx_poll() -> Result<PollWithOptions> {
+        let question = poll_questions::Model {
+            id: 0,
+            public_id: PublicId::default(),
+            plan_id: 0,
+            text: PollQuestionText::new("Where?")?,
+            ctime: OffsetDateTime::now_utc(),
+        };
+        let options = ["Pizza", "Sushi"]
+            .into_iter()
+            .enumerate()
+            .map(|(id, text)| {
+                let option = poll_options::Model {
+                    id: id as i32,
+                    public_id: PublicId::default(),
+                    question_id: 0,
+                    text: PollOptionText::new(text)?,
+                    ctime: OffsetDateTime::now_utc(),
+                };
+                Ok((option, vec![]))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok((question, options))
+    }
+
+    /// Values of all `id` attributes in the html
+    fn ids(html: &str) -> Vec<&str> {
+        html.split(" id=\"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .collect()
+    }
+
+    #[test]
+    fn test_users_update_ids_unique() -> Result<()> {
+        let fx_today = Date::from_calendar_date(2026, Month::October, 19)?;
+        let fx_users_with_dates = vec![
+            fx_user_with_dates(
+                1,
+                "ann",
+                &[fx_today, fx_today.next_day().unwrap_or(fx_today)],
+            )?,
+            fx_user_with_dates(2, "bob", &[fx_today])?,
+            fx_user_with_dates(3, "carl", &[])?,
+        ];
+        let fx_current_user = fx_users_with_dates[0].clone();
+        let fx_settings = CalendarSettings {
+            week_start: Weekday::Monday,
+            show_week_numbers: true,
+            today: fx_today,
+            min_attendance: Some(2),
+            window_start: None,
+            final_date: None,
+        };
+
+        let fx_polls = vec![fx_poll()?];
+
+        for fx_view in CalendarView::ALL {
+            let html = Owner::new().with(|| {
+                view! {
+                    <UsersUpdate
+                        users_with_dates=fx_users_with_dates.clone()
+                        current_user_public_id=Some(fx_current_user.0.public_id.clone())
+                        current_user_with_dates=Some(fx_current_user.clone())
+                        calendar_month=CalendarMonth::current_month(fx_today)
+                        calendar_view=fx_view
+                        calendar_settings=fx_settings
+                        date_notes=DateNotes::new()
+                        polls=fx_polls.clone()
+                        is_owner=true
+                    />
+                }
+                .to_html()
+            });
+            let ids = ids(&html);
+
+            // -- Every swapped component is there once, replacing itself
+            for id in ["users", "polls", "calendar"] {
+                assert_eq!(ids.iter().filter(|other| **other == id).count(), 1, "{id}");
+            }
+            assert_eq!(html.matches(r#"hx-swap-oob="outerHTML""#).count(), 2);
+            assert_eq!(
+                ids.iter().collect::<HashSet<_>>().len(),
+                ids.len(),
+                "{fx_view}: {ids:?}"
+            );
+        }
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
@@ -0,0 +1,231 @@
+//! The owner registers the webhooks of a plan here and sees how their deliveries went
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Response},
+    routing::post,
+    Form, Router,
+};
+use axum_extra::extract::CookieJar;
+use axum_htmx::SwapOption;
+use entity::{
+    db::ModelManager,
+    plans,
+    types::{PublicId, WebhookUrl},
+    webhook_deliveries, webhooks,
+};
+use leptos::prelude::*;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::debug;
+
+use crate::{
+    error::Result,
+    htmx_helpers::{Htmx, HtmxId, HtmxRoute},
+    i18n::{t, Text},
+    plan_page::owner::require_plan_owner,
+    request_context::RequestContext,
+    util_components::Icon,
+};
+
+static WEBHOOKS_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("webhooks"));
+
+/// Deliveries shown in the log, over all webhooks of the plan
+const SHOWN_DELIVERIES: u64 = 10;
+
+pub const WEBHOOKS_ROUTE: HtmxRoute = HtmxRoute::plan("/webhooks");
+
+pub fn routes(mm: ModelManager) -> Router<ModelManager> {
+    Router::new()
+        .route(
+            WEBHOOKS_ROUTE.path(),
+            post(create_webhook_handler).delete(delete_webhook_handler),
+        )
+        .with_state(mm)
+}
+
+// region:	  --- Webhook handlers
+
+#[derive(Debug, Deserialize)]
+struct WebhookPost {
+    url: WebhookUrl,
+}
+
+async fn create_webhook_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Form(webhook_post): Form<WebhookPost>,
+) -> Result<Response> {
+    debug!("{:<12} - create_webhook - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    webhooks::helpers::create_webhook(plan_public_id.clone(), webhook_post.url, mm.clone()).await?;
+
+    render_webhooks(plan_public_id, context, mm).await
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookDelete {
+    webhook: PublicId,
+}
+
+async fn delete_webhook_handler(
+    State(mm): State<ModelManager>,
+    Path(plan_public_id): Path<PublicId>,
+    jar: CookieJar,
+    context: RequestContext,
+    Query(webhook_delete): Query<WebhookDelete>,
+) -> Result<Response> {
+    debug!("{:<12} - delete_webhook - {plan_public_id}", "HANDLER");
+
+    let plan = plans::helpers::plan_by_public_id(plan_public_id.clone(), mm.clone()).await?;
+    require_plan_owner(&plan, &jar)?;
+
+    webhooks::helpers::delete_webhook(plan_public_id.clone(), webhook_delete.webhook, mm.clone())
+        .await?;
+
+    render_webhooks(plan_public_id, context, mm).await
+}
+
+async fn render_webhooks(
+    plan_public_id: PublicId,
+    context: RequestContext,
+    mm: ModelManager,
+) -> Result<Response> {
+    let (webhooks, deliveries) = webhooks_with_deliveries(plan_public_id, mm).await?;
+
+    let view = context.render(|| {
+        view! { <Webhooks webhooks=webhooks deliveries=deliveries open=true/> }
+    });
+    Ok(Html(view).into_response())
+}
+
+/// The webhooks of the plan with their latest deliveries, newest first
+pub async fn webhooks_with_deliveries(
+    plan_public_id: PublicId,
+    mm: ModelManager,
+) -> Result<(Vec<webhooks::Model>, Vec<webhook_deliveries::Model>)> {
+    let webhooks =
+        webhooks::helpers::webhooks_for_plan_public_id(plan_public_id, mm.clone()).await?;
+    let deliveries = webhook_deliveries::helpers::recent_deliveries(
+        webhooks.iter().map(|webhook| webhook.id).collect(),
+        SHOWN_DELIVERIES,
+        mm,
+    )
+    .await?;
+
+    Ok((webhooks, deliveries))
+}
+
+// endregion: --- Webhook handlers
+
+#[component]
+pub fn Webhooks(
+    webhooks: Vec<webhooks::Model>,
+    deliveries: Vec<webhook_deliveries::Model>,
+    /// Stays open after adding or deleting a webhook
+    open: bool,
+) -> impl IntoView {
+    let delivery_rows = deliveries
+        .into_iter()
+        .map(|delivery| {
+            let url = webhooks
+                .iter()
+                .find(|webhook| webhook.id == delivery.webhook_id)
+                .map(|webhook| webhook.url.to_string())
+                .unwrap_or_default();
+            let outcome = match (delivery.status_code, &delivery.error) {
+                (Some(status_code), _) => status_code.to_string(),
+                (None, Some(error)) => error.clone(),
+                (None, None) => "-".to_string(),
+            };
+            let outcome_class = if delivery.is_success() {
+                "text-success"
+            } else {
+                "text-danger"
+            };
+            let ctime = delivery.ctime;
+            let time = format!(
+                "{} {:02}:{:02} UTC",
+                ctime.date(),
+                ctime.hour(),
+                ctime.minute()
+            );
+
+            view! {
+                <li class="border-b border-line py-1" title=url>
+                    <div class="flex justify-between">
+                        <span>{delivery.event} " #" {delivery.attempt}</span>
+                        <span class=outcome_class>{outcome}</span>
+                    </div>
+                    <div class="text-xs">{time}</div>
+                </li>
+            }
+        })
+        .collect_view();
+
+    let webhook_rows = webhooks
+        .into_iter()
+        .map(|webhook| {
+            let vals = json!({ "webhook": webhook.public_id.to_string() });
+
+            view! {
+                <li class="border-b border-line py-2">
+                    <div class="flex items-center justify-between">
+                        <span class="truncate text-content">{webhook.url.to_string()}</span>
+                        <button
+                            type="button"
+                            title=t(Text::DeleteWebhook)
+                            {..Htmx::delete(WEBHOOKS_ROUTE)
+                                .vals(vals)
+                                .target(WEBHOOKS_ID.clone())
+                                .swap(SwapOption::OuterHtml)
+                                .attrs()}
+                        >
+                            <Icon icon=Icon::Delete/>
+                        </button>
+                    </div>
+                    <div class="text-xs">
+                        {t(Text::SigningSecret)} ": " <code>{webhook.secret.expose().to_string()}</code>
+                    </div>
+                </li>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <details id=WEBHOOKS_ID.to_string() class="mx-auto max-w-80 my-8 text-sm text-muted" open=open>
+            <summary>{t(Text::Webhooks)}</summary>
+            <p class="mt-4">{t(Text::WebhooksHint)}</p>
+            <ul class="mt-4">{webhook_rows}</ul>
+            <form
+                {..Htmx::post(WEBHOOKS_ROUTE)
+                    .target(WEBHOOKS_ID.clone())
+                    .swap(SwapOption::OuterHtml)
+                    .attrs()}
+                class="mt-4 space-y-2"
+            >
+                <input
+                    type="url"
+                    name="url"
+                    required
+                    placeholder="https://"
+                    class="w-full rounded-lg border border-line bg-transparent px-2 py-2.5 text-sm text-content"
+                />
+                <button
+                    type="submit"
+                    class="mb-2 w-full rounded-lg bg-control px-5 py-2.5 text-sm font-medium text-content hover:bg-control-hover"
+                >
+                    {t(Text::AddWebhook)}
+                </button>
+            </form>
+            <p class="mt-4">{t(Text::RecentDeliveries)}</p>
+            <ul>{delivery_rows}</ul>
+        </details>
+    }
+}
@@ -0,0 +1,79 @@
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use entity::types::TimeZoneName;
+use leptos::prelude::*;
+
+use crate::{
+    error::{Error, Result},
+    i18n::Locale,
+    middleware::{mw_csrf::CsrfToken, mw_security_headers::CspNonce},
+    theme::Theme,
+};
+
+/// Per-request values that page components need while rendering.
+///
+/// Gathered from the request extensions set by the middlewares and provided as leptos context
+/// by [`RequestContext::render`], so components can get them with `use_context`.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub csrf_token: CsrfToken,
+    pub csp_nonce: CspNonce,
+    pub locale: Locale,
+    pub theme: Theme,
+    /// Zone of the viewer, unknown until the browser reported it
+    pub time_zone: Option<TimeZoneName>,
+    /// Query string of the page, kept by the language and theme links
+    pub query: Option<String>,
+}
+
+impl RequestContext {
+    /// Renders the view to HTML with this context available to all components
+    pub fn render<V: RenderHtml>(self, view: impl FnOnce() -> V) -> String {
+        Owner::new().with(|| {
+            provide_context(self);
+            view().to_html()
+        })
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestContext {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let csrf_token = parts
+            .extensions
+            .get::<CsrfToken>()
+            .cloned()
+            .ok_or(Error::RequestContextMissing("CSRF token"))?;
+
+        let csp_nonce = parts
+            .extensions
+            .get::<CspNonce>()
+            .cloned()
+            .ok_or(Error::RequestContextMissing("CSP nonce"))?;
+
+        let locale = parts
+            .extensions
+            .get::<Locale>()
+            .copied()
+            .ok_or(Error::RequestContextMissing("locale"))?;
+
+        let theme = parts
+            .extensions
+            .get::<Theme>()
+            .copied()
+            .ok_or(Error::RequestContextMissing("theme"))?;
+
+        let time_zone = parts.extensions.get::<TimeZoneName>().cloned();
+        let query = parts.uri.query().map(str::to_string);
+
+        Ok(RequestContext {
+            csrf_token,
+            csp_nonce,
+            locale,
+            theme,
+            time_zone,
+            query,
+        })
+    }
+}
@@ -0,0 +1,84 @@
+//! Fixtures shared by the tests, adjust their fields for the case at hand.
+
+use anyhow::Result;
+use entity::{
+    dates, plans,
+    types::{PlanName, PublicId, TimeZoneName, UserName},
+    users,
+};
+use time::{Date, OffsetDateTime};
+
+use crate::{
+    middleware::{mw_csrf::CsrfToken, mw_security_headers::CspNonce},
+    plan_page::UserWithDates,
+    request_context::RequestContext,
+};
+
+/// A request in English with the system theme, from a viewer whose zone is unknown
+pub fn fx_context() -> RequestContext {
+    RequestContext {
+        csrf_token: CsrfToken::generate(),
+        csp_nonce: CspNonce::generate(),
+        locale: Default::default(),
+        theme: Default::default(),
+        time_zone: None,
+        query: None,
+    }
+}
+
+/// An open plan with the default calendar settings
+pub fn fx_plan(name: &str) -> Result<plans::Model> {
+    Ok(plans::Model {
+        id: 0,
+        public_id: PublicId::default(),
+        name: PlanName::new(name)?,
+        description: None,
+        ctime: OffsetDateTime::now_utc(),
+        mtime: OffsetDateTime::now_utc(),
+        week_start: None,
+        show_week_numbers: false,
+        time_zone: TimeZoneName::default(),
+        owner_secret: None,
+        min_attendance: None,
+        final_date: None,
+        window_start: None,
+    })
+}
+
+/// An optional participant without an email address
+pub fn fx_user(id: i32, name: &str) -> Result<users::Model> {
+    Ok(users::Model {
+        id,
+        public_id: PublicId::default(),
+        name: UserName::new(name)?,
+        plan_id: 0,
+        ctime: OffsetDateTime::now_utc(),
+        required: false,
+        email: None,
+        email_token: None,
+        email_confirmed: false,
+        email_notify: true,
+        digest_sent_at: None,
+        email_locale: None,
+    })
+}
+
+/// A date the user picked
+pub fn fx_date(user_id: i32, date: Date, ctime: OffsetDateTime) -> dates::Model {
+    dates::Model {
+        id: 0,
+        user_id,
+        date,
+        ctime,
+    }
+}
+
+/// A participant with the dates they picked
+pub fn fx_user_with_dates(id: i32, name: &str, picked: &[Date]) -> Result<UserWithDates> {
+    let dates = picked
+        .iter()
+        .map(|date| fx_date(id, *date, OffsetDateTime::now_utc()))
+        .collect();
+
+    Ok((fx_user(id, name)?, dates))
+}
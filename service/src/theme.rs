@@ -0,0 +1,98 @@
+//! Color themes of the UI.
+//!
+//! Components only use the color tokens of `tailwind.config.js` (`bg-surface`, `text-muted`, ...),
+//! their values per theme are CSS variables in `style/input.css`.
+
+use std::str::FromStr;
+
+use derive_more::derive::Display;
+use leptos::prelude::*;
+
+use crate::{
+    i18n::{t, Text},
+    request_context::RequestContext,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display)]
+pub enum Theme {
+    /// Follows `prefers-color-scheme` and `prefers-contrast`
+    #[default]
+    #[display("system")]
+    System,
+    #[display("light")]
+    Light,
+    #[display("dark")]
+    Dark,
+    #[display("contrast")]
+    HighContrast,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [
+        Theme::System,
+        Theme::Light,
+        Theme::Dark,
+        Theme::HighContrast,
+    ];
+
+    /// Value of the `data-theme` attribute, the system theme is left to the media queries
+    pub fn data_theme(self) -> Option<String> {
+        (self != Theme::System).then(|| self.to_string())
+    }
+
+    /// Colors of the browser's own controls and scrollbars
+    pub fn color_scheme(self) -> &'static str {
+        match self {
+            Theme::System => "light dark",
+            Theme::Light => "light",
+            Theme::Dark | Theme::HighContrast => "dark",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::System => t(Text::ThemeSystem),
+            Theme::Light => t(Text::ThemeLight),
+            Theme::Dark => t(Text::ThemeDark),
+            Theme::HighContrast => t(Text::ThemeHighContrast),
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| s.eq_ignore_ascii_case(&theme.to_string()))
+            .ok_or(())
+    }
+}
+
+/// Theme of the request being rendered, see [`RequestContext::render`]
+pub fn use_theme() -> Theme {
+    use_context::<RequestContext>()
+        .map(|context| context.theme)
+        .unwrap_or_default()
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn test_theme_parse_round_trip() -> Result<()> {
+        for theme in Theme::ALL {
+            assert_eq!(theme.to_string().parse(), Ok(theme));
+        }
+        assert_eq!("Dark".parse(), Ok(Theme::Dark));
+        assert_eq!("sepia".parse::<Theme>(), Err(()));
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
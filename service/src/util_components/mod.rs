@@ -1,8 +1,16 @@
-use crate::htmx_helpers::{HtmxId, HtmxInput};
+use crate::{
+    htmx_helpers::{HtmxId, HtmxInput},
+    middleware::mw_csrf::CSRF_FIELD,
+    request_context::RequestContext,
+};
 use std::fmt::Display;
 
 use derive_more::derive::Display;
 use leptos::prelude::*;
+use once_cell::sync::Lazy;
+
+/// Area on every page where error fragments are swapped into
+pub static ERRORS_ID: Lazy<HtmxId> = Lazy::new(|| HtmxId::new("errors"));
 
 #[component]
 pub fn HiddenInput(id: HtmxId, name: String, value: impl Display) -> impl IntoView {
@@ -14,19 +22,19 @@ pub fn HtmxHiddenInput(input: HtmxInput, value: impl Display) -> impl IntoView {
     view! { <input type="hidden" id=input.id.to_string() name=input.name value=value.to_string()/> }
 }
 
-// TODO: This effectively duplicates divs, making it not perfect. Visually it works but it's a bit nasty
-// e.g.
-// <div id=calendar class= ...>
-// Becomes
-// <div id=calendar class= ...>
-//      <div id=calendar class= ...>
+/// The CSRF token for forms that also have to work without htmx, see `mw_csrf`
 #[component]
-pub fn HtmxSwapOob(id: HtmxId, children: Children) -> impl IntoView {
-    view! {
-        <div id=id.to_string() hx-swap-oob="innerHTML">
-            {children()}
-        </div>
-    }
+pub fn CsrfField() -> impl IntoView {
+    let token = use_context::<RequestContext>()
+        .map(|context| context.csrf_token.to_string())
+        .unwrap_or_default();
+
+    view! { <input type="hidden" name=CSRF_FIELD value=token/> }
+}
+
+#[component]
+pub fn ErrorMessage(message: String) -> impl IntoView {
+    view! { <p class="py-2 text-sm text-danger">{message}</p> }
 }
 
 #[derive(Debug, Clone, Copy, Display)]
@@ -48,21 +56,18 @@ pub enum Icon {
 #[component]
 pub fn Icon(icon: Icon) -> impl IntoView {
     view! {
-        <div class="flex items-center justify-center bg-gray-600 p-1 rounded-lg hover:bg-gray-700">
+        <div class="flex items-center justify-center bg-control p-1 rounded-lg hover:bg-control-hover">
             <img src=icon.to_string()/>
         </div>
     }
 }
 
+/// Button that copies `value` when clicked, handled by the script in `Page`
 #[component]
 pub fn CopyToClipboard(value: impl Display, children: Children) -> impl IntoView {
-    let button_id = HtmxId::new("copy_button").to_string();
-    let script = format!("initClipboard('{}', '{}');", button_id, value);
-
     view! {
-        <button id=button_id type="button">
+        <button type="button" data-copy-to-clipboard=value.to_string()>
             {children()}
         </button>
-        <script>{script}</script>
     }
 }
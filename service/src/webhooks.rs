@@ -0,0 +1,562 @@
+//! Delivers plan events to the webhooks of the plan.
+//!
+//! Payloads are JSON, signed with the secret of the webhook: `X-Picktheday-Signature` holds
+//! `sha256=` and the hex HMAC-SHA256 of `{timestamp}.{body}`, the timestamp is sent in
+//! `X-Picktheday-Timestamp`. Failed deliveries are retried with exponential backoff and every
+//! attempt ends up in the delivery log.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use entity::{
+    db::ModelManager,
+    plans,
+    types::is_internal_ip,
+    users,
+    webhook_deliveries::{self, NewWebhookDelivery},
+    webhooks,
+};
+use hmac::{Hmac, Mac};
+use http::{header::CONTENT_TYPE, StatusCode};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use time::{Date, OffsetDateTime};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+use crate::{
+    error::Result,
+    events::{self, PlanEvent},
+};
+
+pub const SIGNATURE_HEADER: &str = "x-picktheday-signature";
+pub const TIMESTAMP_HEADER: &str = "x-picktheday-timestamp";
+pub const EVENT_HEADER: &str = "x-picktheday-event";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// region:	  --- Delivery worker
+
+/// Sends every event to the webhooks of its plan, runs for the lifetime of the server
+pub async fn deliver_events(mm: ModelManager) {
+    let client = http_client();
+    let mut events = events::subscribe();
+
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Err(err) = dispatch(&client, &event, mm.clone()).await {
+                    warn!("{:<12} - dispatch {} - {err}", "WEBHOOKS", event.name());
+                }
+            }
+            Err(RecvError::Lagged(missed)) => {
+                warn!("{:<12} - missed {missed} events", "WEBHOOKS")
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .dns_resolver(Arc::new(PublicResolver))
+        // A redirect could point anywhere, including our own network
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent("picktheday-webhooks")
+        .build()
+        .expect("Webhook client config is valid")
+}
+
+/// Resolves names to their public addresses only, so a webhook host can't point into our own
+/// network through DNS. Ip literals never get here, `WebhookUrl` refuses the internal ones.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| !is_internal_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn dispatch(client: &reqwest::Client, event: &PlanEvent, mm: ModelManager) -> Result<()> {
+    let webhooks =
+        webhooks::helpers::webhooks_for_plan_public_id(event.plan_public_id().clone(), mm.clone())
+            .await?;
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+    let body = payload(event, mm.clone()).await?.to_string();
+
+    // Every webhook retries on its own, so a slow endpoint doesn't hold up the others
+    for webhook in webhooks {
+        let (client, body, mm, event_name) =
+            (client.clone(), body.clone(), mm.clone(), event.name());
+        tokio::spawn(async move {
+            let webhook_id = webhook.id;
+            deliver(
+                &client,
+                webhook.url.as_str(),
+                webhook.secret.expose(),
+                event_name,
+                &body,
+                RetryPolicy::DEFAULT,
+                |attempt| {
+                    tokio::spawn(log_attempt(
+                        webhook_id,
+                        event_name,
+                        attempt.clone(),
+                        mm.clone(),
+                    ));
+                },
+            )
+            .await;
+        });
+    }
+
+    Ok(())
+}
+
+/// The JSON sent for `event`
+async fn payload(event: &PlanEvent, mm: ModelManager) -> Result<Value> {
+    let plan =
+        plans::helpers::plan_by_public_id(event.plan_public_id().clone(), mm.clone()).await?;
+    let user = match event {
+        PlanEvent::UserJoined { user_public_id, .. }
+        | PlanEvent::AvailabilityChanged { user_public_id, .. } => {
+            Some(users::helpers::user_by_public_id(user_public_id.clone(), mm).await?)
+        }
+        PlanEvent::QuorumReached { .. } | PlanEvent::PlanFinalized { .. } => None,
+    };
+
+    Ok(event_payload(
+        event,
+        &plan,
+        user.as_ref(),
+        OffsetDateTime::now_utc(),
+    ))
+}
+
+fn event_payload(
+    event: &PlanEvent,
+    plan: &plans::Model,
+    user: Option<&users::Model>,
+    occurred_at: OffsetDateTime,
+) -> Value {
+    let mut payload = json!({
+        "event": event.name(),
+        "occurred_at": occurred_at.unix_timestamp(),
+        "plan": {
+            "id": plan.public_id.to_string(),
+            "name": plan.name.to_string(),
+        },
+    });
+    if let Some(user) = user {
+        payload["user"] = json!({
+            "id": user.public_id.to_string(),
+            "name": user.name.to_string(),
+        });
+    }
+
+    match event {
+        PlanEvent::UserJoined { .. } => {}
+        PlanEvent::AvailabilityChanged {
+            dates, available, ..
+        } => {
+            payload["dates"] = json!(dates.iter().map(Date::to_string).collect::<Vec<_>>());
+            payload["available"] = json!(available);
+        }
+        PlanEvent::QuorumReached {
+            date,
+            available,
+            min_attendance,
+            ..
+        } => {
+            payload["date"] = json!(date.to_string());
+            payload["attendance"] = json!(available);
+            payload["min_attendance"] = json!(min_attendance);
+        }
+        PlanEvent::PlanFinalized { date, .. } => {
+            payload["date"] = json!(date.to_string());
+        }
+    }
+
+    payload
+}
+
+async fn log_attempt(
+    webhook_id: i32,
+    event_name: &'static str,
+    attempt: DeliveryAttempt,
+    mm: ModelManager,
+) {
+    let delivery = NewWebhookDelivery {
+        webhook_id,
+        event: event_name.to_string(),
+        attempt: attempt.attempt as i32,
+        status_code: attempt.status_code.map(i32::from),
+        error: attempt.error,
+    };
+
+    if let Err(err) = webhook_deliveries::helpers::log_delivery(delivery, mm).await {
+        warn!("{:<12} - log delivery - {err}", "WEBHOOKS");
+    }
+}
+
+// endregion: --- Delivery worker
+
+// region:	  --- Delivery
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// Doubles after every failed attempt
+    pub first_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Gives up after about half a minute
+    pub const DEFAULT: RetryPolicy = RetryPolicy {
+        max_attempts: 5,
+        first_delay: Duration::from_secs(2),
+    };
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.first_delay * 2u32.saturating_pow(attempt.saturating_sub(1))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryAttempt {
+    /// Starts at 1
+    pub attempt: u32,
+    /// `None` when there was no response at all
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl DeliveryAttempt {
+    fn is_success(&self) -> bool {
+        self.status_code
+            .is_some_and(|status_code| (200..300).contains(&status_code))
+    }
+
+    /// Other client errors won't go away by sending the same payload again
+    fn is_retryable(&self) -> bool {
+        match self
+            .status_code
+            .and_then(|code| StatusCode::from_u16(code).ok())
+        {
+            None => true,
+            Some(status) => {
+                status.is_server_error()
+                    || status == StatusCode::REQUEST_TIMEOUT
+                    || status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+/// Posts `body` until the endpoint accepts it or the retries run out.
+///
+/// Calls `on_attempt` after every attempt, returns whether the delivery succeeded.
+pub async fn deliver(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    event_name: &str,
+    body: &str,
+    retry_policy: RetryPolicy,
+    mut on_attempt: impl FnMut(&DeliveryAttempt),
+) -> bool {
+    for attempt in 1..=retry_policy.max_attempts {
+        let delivery_attempt = match send(client, url, secret, event_name, body).await {
+            Ok(status) => DeliveryAttempt {
+                attempt,
+                status_code: Some(status.as_u16()),
+                error: None,
+            },
+            Err(err) => DeliveryAttempt {
+                attempt,
+                status_code: None,
+                error: Some(error_chain(&err.without_url())),
+            },
+        };
+        debug!("{:<12} - {event_name} - {delivery_attempt:?}", "WEBHOOKS");
+        on_attempt(&delivery_attempt);
+
+        if delivery_attempt.is_success() {
+            return true;
+        }
+        if !delivery_attempt.is_retryable() {
+            return false;
+        }
+        if attempt < retry_policy.max_attempts {
+            tokio::time::sleep(retry_policy.delay(attempt)).await;
+        }
+    }
+
+    false
+}
+
+async fn send(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    event_name: &str,
+    body: &str,
+) -> reqwest::Result<StatusCode> {
+    // Signed per attempt, so endpoints can reject old timestamps against replays
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+
+    let response = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event_name)
+        .header(TIMESTAMP_HEADER, timestamp)
+        .header(SIGNATURE_HEADER, signature(secret, timestamp, body))
+        .body(body.to_string())
+        .send()
+        .await?;
+
+    Ok(response.status())
+}
+
+/// The error with its causes, reqwest's own message alone is rarely telling
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message += &format!(": {cause}");
+        source = cause.source();
+    }
+
+    message
+}
+
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// endregion: --- Delivery
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+    #![allow(unused)]
+    use super::*;
+    use crate::test_support::{fx_plan, fx_user};
+    use anyhow::Result;
+    use axum::{extract::State, routing::post, Router};
+    use http::HeaderMap;
+    use std::sync::Mutex;
+    use time::macros::{date, datetime};
+
+    const FX_SECRET: &str = "0123456789abcdefghijklmnopqrstuv";
+    const FX_BODY: &str = r#"{"event":"user_joined"}"#;
+
+    #[derive(Clone, Default)]
+    struct FxEndpoint {
+        /// Answered in order, the last one repeats
+        statuses: Arc<Mutex<Vec<StatusCode>>>,
+        received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    }
+
+    /// Local stand-in for a webhook endpoint, returns its url
+    async fn fx_serve(endpoint: FxEndpoint) -> Result<String> {
+        async fn receive(
+            State(endpoint): State<FxEndpoint>,
+            headers: HeaderMap,
+            body: String,
+        ) -> StatusCode {
+            endpoint.received.lock().unwrap().push((headers, body));
+            let mut statuses = endpoint.statuses.lock().unwrap();
+            if statuses.len() > 1 {
+                statuses.remove(0)
+            } else {
+                statuses[0]
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/hook", listener.local_addr()?);
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(endpoint);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Ok(url)
+    }
+
+    fn fx_endpoint_answering(statuses: &[StatusCode]) -> FxEndpoint {
+        FxEndpoint {
+            statuses: Arc::new(Mutex::new(statuses.to_vec())),
+            ..Default::default()
+        }
+    }
+
+    const FX_RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        first_delay: Duration::from_millis(10),
+    };
+
+    #[tokio::test]
+    async fn test_deliver_signed_with_retries() -> Result<()> {
+        let fx_endpoint =
+            fx_endpoint_answering(&[StatusCode::INTERNAL_SERVER_ERROR, StatusCode::OK]);
+        let url = fx_serve(fx_endpoint.clone()).await?;
+        let mut attempts = vec![];
+
+        let delivered = deliver(
+            &http_client(),
+            &url,
+            FX_SECRET,
+            "user_joined",
+            FX_BODY,
+            FX_RETRY_POLICY,
+            |attempt| attempts.push(attempt.status_code),
+        )
+        .await;
+
+        assert!(delivered);
+        assert_eq!(attempts, vec![Some(500), Some(200)]);
+        let received = fx_endpoint.received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        for (headers, body) in received.iter() {
+            assert_eq!(body, FX_BODY);
+            assert_eq!(headers[EVENT_HEADER], "user_joined");
+            let timestamp = headers[TIMESTAMP_HEADER].to_str()?.parse()?;
+            assert_eq!(
+                headers[SIGNATURE_HEADER],
+                signature(FX_SECRET, timestamp, FX_BODY)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up() -> Result<()> {
+        // -- Client errors are not retried
+        let fx_endpoint = fx_endpoint_answering(&[StatusCode::GONE]);
+        let url = fx_serve(fx_endpoint.clone()).await?;
+        let mut attempts = 0;
+        let client = http_client();
+
+        let delivered = deliver(
+            &client,
+            &url,
+            FX_SECRET,
+            "user_joined",
+            FX_BODY,
+            FX_RETRY_POLICY,
+            |_| attempts += 1,
+        )
+        .await;
+        assert!(!delivered);
+        assert_eq!(attempts, 1);
+
+        // -- Server errors are, until the attempts run out
+        let fx_endpoint = fx_endpoint_answering(&[StatusCode::SERVICE_UNAVAILABLE]);
+        let url = fx_serve(fx_endpoint.clone()).await?;
+        let mut attempts = 0;
+
+        let delivered = deliver(
+            &client,
+            &url,
+            FX_SECRET,
+            "user_joined",
+            FX_BODY,
+            FX_RETRY_POLICY,
+            |_| attempts += 1,
+        )
+        .await;
+        assert!(!delivered);
+        assert_eq!(attempts, FX_RETRY_POLICY.max_attempts);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_public_resolver_refuses_internal_addresses() -> Result<()> {
+        let resolved = PublicResolver.resolve("localhost".parse()?).await;
+        assert!(resolved.is_err());
+
+        // -- The client connects through it
+        let fx_endpoint = fx_endpoint_answering(&[StatusCode::OK]);
+        let url = fx_serve(fx_endpoint.clone())
+            .await?
+            .replace("127.0.0.1", "localhost");
+        let mut errors = vec![];
+
+        let delivered = deliver(
+            &http_client(),
+            &url,
+            FX_SECRET,
+            "user_joined",
+            FX_BODY,
+            RetryPolicy {
+                max_attempts: 1,
+                ..FX_RETRY_POLICY
+            },
+            |attempt| errors.push(attempt.error.clone()),
+        )
+        .await;
+
+        assert!(!delivered);
+        assert!(errors[0]
+            .as_ref()
+            .is_some_and(|error| error.contains("no public address")));
+        assert!(fx_endpoint.received.lock().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_payload_availability_changed() -> Result<()> {
+        let fx_plan = fx_plan("Game night")?;
+        let fx_user = fx_user(1, "alice")?;
+        let fx_event = PlanEvent::AvailabilityChanged {
+            plan_public_id: fx_plan.public_id.clone(),
+            user_public_id: fx_user.public_id.clone(),
+            dates: vec![date!(2024 - 10 - 25), date!(2024 - 10 - 26)],
+            available: true,
+        };
+
+        let payload = event_payload(
+            &fx_event,
+            &fx_plan,
+            Some(&fx_user),
+            datetime!(2024-10-20 12:00 UTC),
+        );
+
+        assert_eq!(
+            payload,
+            json!({
+                "event": "availability_changed",
+                "occurred_at": 1729425600,
+                "plan": { "id": fx_plan.public_id.to_string(), "name": "Game night" },
+                "user": { "id": fx_user.public_id.to_string(), "name": "alice" },
+                "dates": ["2024-10-25", "2024-10-26"],
+                "available": true,
+            })
+        );
+
+        Ok(())
+    }
+}
+// endregion: --- Tests
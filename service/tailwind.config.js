@@ -3,13 +3,35 @@ const { fontFamily } = require("tailwindcss/defaultTheme");
 /** @type {import('tailwindcss').Config} */
 module.exports = {
     content: {
-        files: ["*.html", "./src/**/*.rs"],
+        files: ["*.html", "./src/**/*.rs", "../public/*.js"],
     },
     theme: {
         extend: {
             fontFamily: {
                 sans: ["Inter var", ...fontFamily.sans],
             },
+            // Themed tokens, their values are CSS variables in style/input.css
+            colors: {
+                "surface": "rgb(var(--color-surface) / <alpha-value>)",
+                "raised": "rgb(var(--color-raised) / <alpha-value>)",
+                "highlight": "rgb(var(--color-highlight) / <alpha-value>)",
+                "popover": "rgb(var(--color-popover) / <alpha-value>)",
+                "content": "rgb(var(--color-content) / <alpha-value>)",
+                "muted": "rgb(var(--color-muted) / <alpha-value>)",
+                "faint": "rgb(var(--color-faint) / <alpha-value>)",
+                "line": "rgb(var(--color-line) / <alpha-value>)",
+                "control": "rgb(var(--color-control) / <alpha-value>)",
+                "control-hover": "rgb(var(--color-control-hover) / <alpha-value>)",
+                "accent": "rgb(var(--color-accent) / <alpha-value>)",
+                "heat-1": "rgb(var(--color-heat-1) / <alpha-value>)",
+                "heat-2": "rgb(var(--color-heat-2) / <alpha-value>)",
+                "heat-3": "rgb(var(--color-heat-3) / <alpha-value>)",
+                "heat-4": "rgb(var(--color-heat-4) / <alpha-value>)",
+                "quorum": "rgb(var(--color-quorum) / <alpha-value>)",
+                "note": "rgb(var(--color-note) / <alpha-value>)",
+                "success": "rgb(var(--color-success) / <alpha-value>)",
+                "danger": "rgb(var(--color-danger) / <alpha-value>)",
+            },
         },
     },
     plugins: [],